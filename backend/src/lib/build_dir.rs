@@ -0,0 +1,129 @@
+//! Throwaway copies of a TeeBench directory, so that `compile` never touches the sources or binaries that jobs use.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::BIN_FOLDER;
+
+/// Folder in the TeeBench directory that holds the copies.
+const BUILDS_FOLDER: &str = ".tbw-builds";
+/// Not copied: the binaries are promoted into `bin` after a successful build, and git is only needed for `BuildFingerprint::of_sources`.
+const SKIPPED: [&str; 3] = [BIN_FOLDER, BUILDS_FOLDER, ".git"];
+
+/// A copy of a TeeBench directory that is removed when it is dropped, also when the job that compiles in it is cancelled.
+///
+/// Files are hard linked where possible, so a copy is cheap. The build must therefore replace files instead of writing into them: `compile` runs `make clean` first, which removes the outputs of earlier builds.
+#[derive(Debug)]
+pub struct BuildDir {
+    pub path: PathBuf,
+}
+
+impl BuildDir {
+    pub async fn new(tee_bench_dir: &Path) -> Result<Self> {
+        let path = tee_bench_dir
+            .join(BUILDS_FOLDER)
+            .join(format!("{:016x}", rand::random::<u64>()));
+        let build = Self { path };
+        let (src, dest) = (tee_bench_dir.to_path_buf(), build.path.clone());
+        tokio::task::spawn_blocking(move || copy_tree(&src, &dest, true))
+            .await?
+            .with_context(|| format!("Failed to copy {tee_bench_dir:?} to {:?}", build.path))?;
+        debug!("Copied {tee_bench_dir:?} to {:?}", build.path);
+        Ok(build)
+    }
+
+    /// Writes `contents` to `file` in the copy, without changing the file it is linked to.
+    pub async fn replace_file(&self, file: &str, contents: &str) -> Result<()> {
+        let path = self.path.join(file);
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                return Err(e).with_context(|| format!("Failed to remove {path:?}"))
+            }
+            _ => (),
+        }
+        tokio::fs::write(&path, contents)
+            .await
+            .with_context(|| format!("Failed to write {path:?}"))
+    }
+}
+
+impl Drop for BuildDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            warn!("Failed to remove {:?}: {e}", self.path);
+        }
+    }
+}
+
+/// Removes the copies that a crashed or killed server left behind. Only call it while no build runs in `tee_bench_dir`.
+pub async fn remove_stale_builds(tee_bench_dir: &Path) {
+    let path = tee_bench_dir.join(BUILDS_FOLDER);
+    match tokio::fs::remove_dir_all(&path).await {
+        Ok(()) => debug!("Removed the stale builds in {path:?}"),
+        Err(e) if e.kind() == ErrorKind::NotFound => (),
+        Err(e) => warn!("Failed to remove {path:?}: {e}"),
+    }
+}
+
+/// Recreates the tree `src` in `dest`. `top` skips the `SKIPPED` folders of the TeeBench directory.
+fn copy_tree(src: &Path, dest: &Path, top: bool) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if top && SKIPPED.iter().any(|s| name == *s) {
+            continue;
+        }
+        let (from, to) = (entry.path(), dest.join(&name));
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_tree(&from, &to, false)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(&from)?, &to)?;
+        } else if fs::hard_link(&from, &to).is_err() {
+            fs::copy(&from, &to).with_context(|| format!("Failed to copy {from:?}"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_dir() -> Result<()> {
+        let tee_bench_dir = std::env::temp_dir().join(format!("tb-{:016x}", rand::random::<u64>()));
+        fs::create_dir_all(tee_bench_dir.join("Joins/TBW"))?;
+        fs::create_dir_all(tee_bench_dir.join(BIN_FOLDER))?;
+        fs::write(tee_bench_dir.join("Joins/TBW/OperatorJoin.cpp"), "original")?;
+        fs::write(tee_bench_dir.join("Makefile"), "all:")?;
+        fs::write(tee_bench_dir.join(BIN_FOLDER).join("sgx"), "binary")?;
+        let build = BuildDir::new(&tee_bench_dir).await?;
+        let path = build.path.clone();
+        assert_eq!(fs::read_to_string(path.join("Makefile"))?, "all:");
+        assert!(!path.join(BIN_FOLDER).exists());
+        build
+            .replace_file("Joins/TBW/OperatorJoin.cpp", "operator")
+            .await?;
+        assert_eq!(
+            fs::read_to_string(path.join("Joins/TBW/OperatorJoin.cpp"))?,
+            "operator"
+        );
+        assert_eq!(
+            fs::read_to_string(tee_bench_dir.join("Joins/TBW/OperatorJoin.cpp"))?,
+            "original"
+        );
+        drop(build);
+        assert!(!path.exists());
+        let stale = BuildDir::new(&tee_bench_dir).await?;
+        std::mem::forget(stale);
+        remove_stale_builds(&tee_bench_dir).await;
+        assert!(!tee_bench_dir.join(BUILDS_FOLDER).exists());
+        fs::remove_dir_all(&tee_bench_dir)?;
+        Ok(())
+    }
+}
@@ -1,31 +1,37 @@
-use anyhow::{bail, Result};
-use rusqlite::{params, Connection};
+use anyhow::{bail, Context, Result};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
 use rusqlite_migration::{Migrations, M};
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::env::var;
+use std::iter::zip;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tracing::debug;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, info, warn};
 
 use crate::config::{
     EMPTY_CACHE_VAR_NAME, OUTPUT_CSV_PATH, SQLITE_FILE_VAR_NAME, TEEBENCHARGS_CSV_PATH,
 };
-use common::data_types::TeebenchArgs;
-
-/// When SQLite imports csv, empty cells are set to "", because csv does not support NULL.
-fn set_to_null_if_equals_empty_string(table: &str, column: &str) -> String {
-    format!(
-        r#"
-        UPDATE {table}
-            SET {column} = NULL
-            WHERE {column} = "";
-    "#
-    )
-}
+use crate::fingerprint::BuildFingerprint;
+use crate::operator_hash;
+use common::commit::{Commit, CommitIdType, CompilationStatus};
+use common::data_types::{
+    Algorithm, BuildProfile, CacheStatistics, Job, JobHistoryPage, JobIdType, JobPriority,
+    JobStatus, TeebenchArgs,
+};
+use common::output_schema::{
+    get_column, is_valid_column_name, ColumnType, OutputColumn, OUTPUT_COLUMNS,
+};
 
 lazy_static::lazy_static! {
-    // TODO To make the TB csv output easier to change: Load a config file with the csv column names (and types?) as another static and then use it in `MIGRATIONS` to create the table.
     static ref MIGRATIONS: Migrations<'static> = {
+        fn string_to_static_str(s: String) -> &'static str {
+            Box::leak(s.into_boxed_str())
+        }
+        let output_columns = OUTPUT_COLUMNS.iter().map(OutputColumn::to_sql).collect::<Vec<_>>().join(",\n");
         let v1 = r#"
             CREATE TABLE teebenchargs(
                 id INTEGER PRIMARY KEY,
@@ -46,117 +52,103 @@ lazy_static::lazy_static! {
                 sort_r INTEGER NOT NULL,
                 sort_s INTEGER NOT NULL
             );
-            CREATE TABLE output(
-                teebenchargs_id INTEGER PRIMARY KEY,
-                algorithm TEXT NOT NULL,
-                threads INTEGER NOT NULL,
-                relR INTEGER NOT NULL,
-                relS INTEGER NOT NULL,
-                matches INTEGER NOT NULL,
-                phase1Cycles INTEGER NOT NULL,
-                phase2Cycles INTEGER NOT NULL,
-                cyclesPerTuple INTEGER NOT NULL,
-                phase1Time INTEGER NOT NULL,
-                phase2Time INTEGER NOT NULL,
-                totalTime INTEGER NOT NULL,
-                throughput REAL NOT NULL,
-                phase1L3CacheMisses INTEGER,
-                phase1L3HitRatio REAL,
-                phase1L2CacheMisses INTEGER,
-                phase1L2HitRatio REAL,
-                phase1IPC REAL,
-                phase1IR INTEGER,
-                phase1EWB INTEGER,
-                phase1VoluntaryCS INTEGER,
-                phase1InvoluntaryCS INTEGER,
-                phase1UserCpuTime INTEGER,
-                phase1SystemCpuTime INTEGER,
-                phase2L3CacheMisses INTEGER,
-                phase2L3HitRatio REAL,
-                phase2L2CacheMisses INTEGER,
-                phase2L2HitRatio REAL,
-                phase2IPC REAL,
-                phase2IR INTEGER,
-                phase2EWB INTEGER,
-                phase2VoluntaryCS INTEGER,
-                phase2InvoluntaryCS INTEGER,
-                phase2UserCpuTime INTEGER,
-                phase2SystemCpuTime INTEGER,
-                totalL3CacheMisses INTEGER,
-                totalL3HitRatio REAL,
-                totalL2CacheMisses INTEGER,
-                totalL2HitRatio REAL,
-                totalIPC REAL,
-                totalIR INTEGER,
-                totalEWB INTEGER,
-                totalVoluntaryCS INTEGER,
-                totalInvoluntaryCS INTEGER,
-                totalUserCpuTime INTEGER,
-                totalSystemCpuTime INTEGER
+        "#;
+        let v1 = format!("{v1}\nCREATE TABLE output(\nteebenchargs_id INTEGER PRIMARY KEY,\n{output_columns}\n);");
+        // Leaking `v1` is discouraged but the only way to continue using the migrations library.
+        let v1 = string_to_static_str(v1);
+        // Migration 2 used to load the csv files, this is now done by `import_csv`. It has to stay in place so that later migrations keep their version.
+        let v2 = "";
+        let v3 = r#"
+            CREATE TABLE commits(
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                version TEXT NOT NULL,
+                operator TEXT NOT NULL,
+                datetime TEXT NOT NULL,
+                code TEXT NOT NULL,
+                baseline TEXT NOT NULL,
+                compilation TEXT NOT NULL,
+                compile_output BLOB,
+                perf_report_running TEXT NOT NULL,
+                report BLOB
             );
         "#;
-        let optionals_args = ["r_size", "s_size", "r_path", "s_path"];
-        let optionals_args = optionals_args.map(|s| set_to_null_if_equals_empty_string("teebenchargs", s)).join("");
-        let optionals_output = [
-            "phase1L3CacheMisses",
-            "phase1L3HitRatio",
-            "phase1L2CacheMisses",
-            "phase1L2HitRatio",
-            "phase1IPC",
-            "phase1IR",
-            "phase1EWB",
-            "phase1VoluntaryCS",
-            "phase1InvoluntaryCS",
-            "phase1UserCpuTime",
-            "phase1SystemCpuTime",
-            "phase2L3CacheMisses",
-            "phase2L3HitRatio",
-            "phase2L2CacheMisses",
-            "phase2L2HitRatio",
-            "phase2IPC",
-            "phase2IR",
-            "phase2EWB",
-            "phase2VoluntaryCS",
-            "phase2InvoluntaryCS",
-            "phase2UserCpuTime",
-            "phase2SystemCpuTime",
-            "totalL3CacheMisses",
-            "totalL3HitRatio",
-            "totalL2CacheMisses",
-            "totalL2HitRatio",
-            "totalIPC",
-            "totalIR",
-            "totalEWB",
-            "totalVoluntaryCS",
-            "totalInvoluntaryCS",
-            "totalUserCpuTime",
-            "totalSystemCpuTime",
-        ];
-        let optionals_output = optionals_output.map(|s| set_to_null_if_equals_empty_string("output", s)).join("");
-        fn string_to_static_str(s: String) -> &'static str {
-            Box::leak(s.into_boxed_str())
-        }
-        let v2 = format!(r#"
-            CREATE VIRTUAL TABLE teebenchargs_csv
-                USING csv(filename={}, header=YES);
-            CREATE VIRTUAL TABLE output_csv
-                USING csv(filename={}, header=YES);
-            INSERT INTO teebenchargs SELECT * FROM teebenchargs_csv;
-            INSERT INTO output SELECT * FROM output_csv;
-            {}
-            {}
-            DROP TABLE teebenchargs_csv;
-            DROP TABLE output_csv;
-        "#, TEEBENCHARGS_CSV_PATH, OUTPUT_CSV_PATH, optionals_args, optionals_output);
-        // Leaking `v2` is discouraged but the only way to continue using the migrations library.
-        let v2 = string_to_static_str(v2);
-        let v2_down = r#"
-            DROP TABLE teebenchargs;
-            DROP TABLE output;
+        let v3_down = "DROP TABLE commits;";
+        let v4 = r#"
+            CREATE TABLE jobs(
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                config BLOB NOT NULL,
+                submitted TEXT NOT NULL,
+                status TEXT NOT NULL,
+                runtime_ns INTEGER,
+                result BLOB
+            );
+        "#;
+        let v4_down = "DROP TABLE jobs;";
+        // Results loaded from the csv files have no fingerprint, they are only used if historical data is included.
+        let v5 = r#"
+            ALTER TABLE teebenchargs ADD COLUMN fingerprint TEXT;
+            CREATE TABLE builds(
+                fingerprint TEXT PRIMARY KEY,
+                revision TEXT NOT NULL,
+                local_changes TEXT,
+                flags TEXT NOT NULL,
+                compiler TEXT NOT NULL,
+                first_seen TEXT NOT NULL
+            );
+        "#;
+        let v5_down = r#"
+            DROP TABLE builds;
+            ALTER TABLE teebenchargs DROP COLUMN fingerprint;
+        "#;
+        // Like the fingerprint, this is NULL for the results from the csv files, so they are never pruned by age.
+        let v6 = "ALTER TABLE teebenchargs ADD COLUMN created TEXT;";
+        let v6_down = "ALTER TABLE teebenchargs DROP COLUMN created;";
+        // Speeds up `search_for_exp` and the duplicate check of `import_csv`.
+        let v7 = "CREATE INDEX teebenchargs_lookup ON teebenchargs(algorithm, app_name, dataset, threads);";
+        let v7_down = "DROP INDEX teebenchargs_lookup;";
+        // Where results that were not measured by this instance come from, see `insert_experiment`.
+        let v8 = "ALTER TABLE teebenchargs ADD COLUMN source TEXT;";
+        let v8_down = "ALTER TABLE teebenchargs DROP COLUMN source;";
+        // Waiting jobs are sorted by priority and then by position, see `common::queue`. Jobs that were never moved have no position and come last.
+        let v9 = r#"
+            ALTER TABLE jobs ADD COLUMN priority TEXT NOT NULL DEFAULT 'Normal';
+            ALTER TABLE jobs ADD COLUMN position INTEGER;
+        "#;
+        let v9_down = r#"
+            ALTER TABLE jobs DROP COLUMN position;
+            ALTER TABLE jobs DROP COLUMN priority;
+        "#;
+        // Server state that has to survive a restart, eg. whether the queue is paused.
+        let v10 = r#"
+            CREATE TABLE settings(
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+        "#;
+        let v10_down = "DROP TABLE settings;";
+        // The `BuildProfile` of a run, results of different profiles are never mixed. The profile of a commit is stored as json, NULL is the default profile.
+        let v11 = r#"
+            ALTER TABLE teebenchargs ADD COLUMN profile TEXT NOT NULL DEFAULT 'default';
+            ALTER TABLE commits ADD COLUMN profile TEXT;
+        "#;
+        let v11_down = r#"
+            ALTER TABLE commits DROP COLUMN profile;
+            ALTER TABLE teebenchargs DROP COLUMN profile;
         "#;
         Migrations::new(vec![
             M::up(v1),
-            M::up(v2).down(v2_down),
+            M::up(v2),
+            M::up(v3).down(v3_down),
+            M::up(v4).down(v4_down),
+            M::up(v5).down(v5_down),
+            M::up(v6).down(v6_down),
+            M::up(v7).down(v7_down),
+            M::up(v8).down(v8_down),
+            M::up(v9).down(v9_down),
+            M::up(v10).down(v10_down),
+            M::up(v11).down(v11_down),
         ])
     };
 }
@@ -166,73 +158,654 @@ pub fn setup_sqlite() -> Result<Connection> {
         var(SQLITE_FILE_VAR_NAME).unwrap_or_else(|_| panic!("{SQLITE_FILE_VAR_NAME} not set")),
     );
     let mut conn = Connection::open(sqlite_dir)?;
-    let do_not_load_csv = var(EMPTY_CACHE_VAR_NAME).is_ok();
-    if do_not_load_csv {
-        MIGRATIONS.to_version(&mut conn, 1)?;
-    } else {
-        rusqlite::vtab::csvtab::load_module(&conn)?;
-        MIGRATIONS.to_latest(&mut conn)?;
+    migrate(&mut conn)?;
+    let is_empty: bool =
+        conn.query_row("SELECT NOT EXISTS(SELECT 1 FROM teebenchargs)", [], |r| {
+            r.get(0)
+        })?;
+    if is_empty && var(EMPTY_CACHE_VAR_NAME).is_err() {
+        let (imported, _) = import_csv_into(
+            &conn,
+            open_csv(TEEBENCHARGS_CSV_PATH)?,
+            open_csv(OUTPUT_CSV_PATH)?,
+        )?;
+        info!("Seeded the empty cache with {imported} run(s) from {TEEBENCHARGS_CSV_PATH}.");
     }
+    Ok(conn)
+}
 
+/// Opens the cache of a `tbw-worker`, in memory if there is no `path`. It is not seeded, the server has the historical results.
+pub fn setup_worker_sqlite(path: Option<&str>) -> Result<Connection> {
+    let mut conn = match path {
+        Some(path) => Connection::open(path)?,
+        None => Connection::open_in_memory()?,
+    };
+    migrate(&mut conn)?;
     Ok(conn)
 }
 
+fn migrate(conn: &mut Connection) -> Result<()> {
+    MIGRATIONS.to_latest(conn)?;
+    add_missing_output_columns(conn)?;
+    Ok(())
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let names = stmt.query_map([], |r| r.get::<&str, String>("name"))?;
+    Ok(names.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Adds the columns of `OUTPUT_COLUMNS` that the `output` table does not have yet.
+///
+/// SQLite cannot add `NOT NULL` columns to a table with rows, so added columns are always nullable. `insert_experiment` still checks for required columns.
+fn add_missing_output_columns(conn: &Connection) -> Result<()> {
+    let existing = table_columns(conn, "output")?;
+    for column in OUTPUT_COLUMNS {
+        if !existing.iter().any(|e| e == column.name) {
+            info!("Adding new column `{}` to table `output`.", column.name);
+            let column = OutputColumn {
+                required: false,
+                ..*column
+            };
+            conn.execute(
+                &format!("ALTER TABLE output ADD COLUMN {}", column.to_sql()),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Inserts the commit or, if a commit with the same id already exists, updates all of its fields.
+pub fn save_commit(conn: Arc<Mutex<Connection>>, commit: &Commit) -> Result<()> {
+    let (compilation, compile_output) = match &commit.compilation {
+        CompilationStatus::Uncompiled => ("Uncompiled", None),
+        CompilationStatus::Compiling => ("Compiling", None),
+        CompilationStatus::Successful(o) => ("Successful", Some(o.as_bytes())),
+        CompilationStatus::Failed(o) => ("Failed", Some(o.as_bytes())),
+    };
+    let report = match &commit.report {
+        Some(r) => Some(serde_json::to_vec(r)?),
+        None => None,
+    };
+    let profile = match commit.profile.is_default() {
+        true => None,
+        false => Some(serde_json::to_string(&commit.profile)?),
+    };
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO commits (id, title, version, operator, datetime, code, baseline, compilation, compile_output, perf_report_running, report, profile) \
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12) \
+            ON CONFLICT(id) DO UPDATE SET title=excluded.title, version=excluded.version, operator=excluded.operator, \
+            datetime=excluded.datetime, code=excluded.code, baseline=excluded.baseline, compilation=excluded.compilation, \
+            compile_output=excluded.compile_output, perf_report_running=excluded.perf_report_running, report=excluded.report, \
+            profile=excluded.profile",
+        params![
+            commit.id.to_string(),
+            commit.title,
+            commit.version,
+            commit.operator.to_string(),
+            commit.datetime.format(&Rfc3339)?,
+            commit.code,
+            serde_json::to_string(&commit.baseline)?,
+            compilation,
+            compile_output,
+            serde_json::to_string(&commit.perf_report_running)?,
+            report,
+            profile,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Returns all stored commits in the order they were uploaded.
+pub fn load_commits(conn: Arc<Mutex<Connection>>) -> Result<Vec<Commit>> {
+    type CommitRow = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        Option<Vec<u8>>,
+        String,
+        Option<Vec<u8>>,
+        Option<String>,
+    );
+    let rows: Vec<CommitRow> = {
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id, title, version, operator, datetime, code, baseline, compilation, compile_output, perf_report_running, report, profile FROM commits ORDER BY rowid")?;
+        let rows = stmt.query_map([], |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+                r.get(6)?,
+                r.get(7)?,
+                r.get(8)?,
+                r.get(9)?,
+                r.get(10)?,
+                r.get(11)?,
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    let mut commits = vec![];
+    for (
+        id,
+        title,
+        version,
+        operator,
+        datetime,
+        code,
+        baseline,
+        compilation,
+        compile_output,
+        perf_report_running,
+        report,
+        profile,
+    ) in rows
+    {
+        let compile_output = compile_output
+            .map(String::from_utf8)
+            .transpose()?
+            .unwrap_or_default();
+        let compilation = match compilation.as_str() {
+            "Uncompiled" => CompilationStatus::Uncompiled,
+            "Compiling" => CompilationStatus::Compiling,
+            "Successful" => CompilationStatus::Successful(compile_output),
+            "Failed" => CompilationStatus::Failed(compile_output),
+            other => bail!("Unknown compilation status `{other}` for commit {id}"),
+        };
+        let report = match report {
+            Some(r) => Some(serde_json::from_slice(&r)?),
+            None => None,
+        };
+        commits.push(Commit {
+            title,
+            version,
+            operator: operator
+                .parse()
+                .with_context(|| format!("Unknown operator `{operator}` for commit {id}"))?,
+            datetime: OffsetDateTime::parse(&datetime, &Rfc3339)?,
+            code,
+            report,
+            id: CommitIdType::parse_str(&id)?,
+            compilation,
+            perf_report_running: serde_json::from_str(&perf_report_running)?,
+            baseline: serde_json::from_str(&baseline)?,
+            profile: match profile {
+                Some(p) => serde_json::from_str(&p)?,
+                None => BuildProfile::default(),
+            },
+        });
+    }
+    Ok(commits)
+}
+
+/// Inserts the job or, if a job with the same id already exists, updates its status and result.
+///
+/// A running job is stored as `Waiting`, so a job that is stopped by a shutdown or crash is loaded into the queue again by `load_waiting_jobs` after a restart. Its finished runs are cached, so it continues where it stopped.
+pub fn save_job(conn: Arc<Mutex<Connection>>, job: &Job) -> Result<()> {
+    let (status, runtime_ns) = match job.status {
+        JobStatus::Waiting | JobStatus::Running => ("Waiting", None),
+        JobStatus::Done { runtime } => ("Done", Some(runtime.whole_nanoseconds() as i64)),
+    };
+    let result = match &job.result {
+        Some(r) => Some(serde_json::to_vec(r)?),
+        None => None,
+    };
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO jobs (id, kind, config, submitted, status, runtime_ns, result, priority) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) \
+            ON CONFLICT(id) DO UPDATE SET status=excluded.status, runtime_ns=excluded.runtime_ns, result=excluded.result, priority=excluded.priority",
+        params![
+            job.id.to_string(),
+            job.config.kind(),
+            serde_json::to_vec(&job.config)?,
+            job.submitted.format(&Rfc3339)?,
+            status,
+            runtime_ns,
+            result,
+            job.priority.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Stores the order and priorities of the queue (see `common::queue::queue_order`), so it is restored after a restart.
+pub fn save_queue_order(
+    conn: Arc<Mutex<Connection>>,
+    order: &[(JobIdType, JobPriority)],
+) -> Result<()> {
+    let mut conn = conn.lock().unwrap();
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare("UPDATE jobs SET priority=?1, position=?2 WHERE id=?3")?;
+        for (position, (id, priority)) in order.iter().enumerate() {
+            stmt.execute(params![priority.to_string(), position, id.to_string()])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Stores whether the queue is paused, so that it stays paused after a restart (eg. during maintenance).
+pub fn save_queue_paused(conn: Arc<Mutex<Connection>>, paused: bool) -> Result<()> {
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('queue_paused', ?1) \
+            ON CONFLICT(key) DO UPDATE SET value=excluded.value",
+        [paused.to_string()],
+    )?;
+    Ok(())
+}
+
+pub fn load_queue_paused(conn: Arc<Mutex<Connection>>) -> Result<bool> {
+    let conn = conn.lock().unwrap();
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key='queue_paused'",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Removes the job with `id` if it has not run yet.
+pub fn delete_waiting_job(conn: Arc<Mutex<Connection>>, id: &JobIdType) -> Result<()> {
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "DELETE FROM jobs WHERE id=?1 AND status='Waiting'",
+        [id.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Removes all jobs that have not run yet.
+pub fn delete_waiting_jobs(conn: Arc<Mutex<Connection>>) -> Result<()> {
+    let conn = conn.lock().unwrap();
+    conn.execute("DELETE FROM jobs WHERE status='Waiting'", [])?;
+    Ok(())
+}
+
+type JobRow = (
+    String,
+    Vec<u8>,
+    String,
+    String,
+    Option<i64>,
+    Option<Vec<u8>>,
+    String,
+);
+
+fn job_from_row(row: JobRow) -> Result<Job> {
+    let (id, config, submitted, status, runtime_ns, result, priority) = row;
+    let status = match (status.as_str(), runtime_ns) {
+        ("Waiting", _) => JobStatus::Waiting,
+        ("Done", Some(ns)) => JobStatus::Done {
+            runtime: Duration::nanoseconds(ns),
+        },
+        (other, _) => bail!("Invalid status `{other}` for job {id}"),
+    };
+    let result = match result {
+        Some(r) => Some(serde_json::from_slice(&r)?),
+        None => None,
+    };
+    Ok(Job {
+        config: serde_json::from_slice(&config)?,
+        submitted: OffsetDateTime::parse(&submitted, &Rfc3339)?,
+        result,
+        status,
+        id: JobIdType::parse_str(&id)?,
+        priority: priority
+            .parse::<JobPriority>()
+            .with_context(|| format!("Invalid priority `{priority}` for job {id}"))?,
+        progress: None,
+    })
+}
+
+fn query_jobs(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Job>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, |r| {
+        Ok((
+            r.get(0)?,
+            r.get(1)?,
+            r.get(2)?,
+            r.get(3)?,
+            r.get(4)?,
+            r.get(5)?,
+            r.get(6)?,
+        ))
+    })?;
+    rows.map(|r| job_from_row(r?)).collect()
+}
+
+/// Returns the jobs that have not run yet, in the order of the queue (see `save_queue_order`).
+pub fn load_waiting_jobs(conn: Arc<Mutex<Connection>>) -> Result<Vec<Job>> {
+    let conn = conn.lock().unwrap();
+    let mut jobs = query_jobs(
+        &conn,
+        "SELECT id, config, submitted, status, runtime_ns, result, priority FROM jobs WHERE status='Waiting' \
+            ORDER BY position IS NULL, position, rowid",
+        [],
+    )?;
+    // Stable, so the jobs keep their order within a priority class.
+    jobs.sort_by_key(|j| Reverse(j.priority));
+    Ok(jobs)
+}
+
+/// Returns up to `limit` finished jobs, newest first, skipping the first `offset`.
+///
+/// kind: only return jobs whose `JobConfig::kind` matches.
+pub fn load_finished_jobs(
+    conn: Arc<Mutex<Connection>>,
+    kind: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> Result<JobHistoryPage> {
+    let conn = conn.lock().unwrap();
+    let total = conn.query_row(
+        "SELECT COUNT(*) FROM jobs WHERE status='Done' AND (?1 IS NULL OR kind=?1)",
+        [kind],
+        |r| r.get::<usize, usize>(0),
+    )?;
+    let jobs = query_jobs(
+        &conn,
+        "SELECT id, config, submitted, status, runtime_ns, result, priority FROM jobs WHERE status='Done' AND (?1 IS NULL OR kind=?1) \
+            ORDER BY rowid DESC LIMIT ?2 OFFSET ?3",
+        params![kind, limit, offset],
+    )?;
+    Ok(JobHistoryPage { jobs, total })
+}
+
+/// How `alg` is stored in the `algorithm` column of `teebenchargs`.
+///
+/// Operators are identified by the hash of their code instead of their commit id, so identical operators share results and changed code never finds old ones.
+pub fn algorithm_key(alg: &Algorithm, code_hashmap: &HashMap<Algorithm, String>) -> Result<String> {
+    match alg {
+        Algorithm::Commit(id) => match code_hashmap.get(alg) {
+            Some(code) => Ok(format!("Operator({})", operator_hash(code))),
+            None => bail!("No code for operator {id}"),
+        },
+        a => Ok(format!("{a:?}")),
+    }
+}
+
+/// Remembers what `fingerprint.hash()` stands for. Does nothing if the build is already known.
+pub fn save_build(conn: Arc<Mutex<Connection>>, fingerprint: &BuildFingerprint) -> Result<()> {
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR IGNORE INTO builds (fingerprint, revision, local_changes, flags, compiler, first_seen) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            fingerprint.hash(),
+            fingerprint.revision,
+            fingerprint.local_changes,
+            fingerprint.flags,
+            fingerprint.compiler,
+            OffsetDateTime::now_utc().format(&Rfc3339)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Columns of `teebenchargs` in the csv files, besides `id`.
+const CSV_ARGS_COLUMNS: [&str; 16] = [
+    "app_name",
+    "dataset",
+    "algorithm",
+    "threads",
+    "selectivity",
+    "data_skew",
+    "seal_chunk_size",
+    "r_tuples",
+    "s_tuples",
+    "r_path",
+    "s_path",
+    "r_size",
+    "s_size",
+    "seal",
+    "sort_r",
+    "sort_s",
+];
+
+fn open_csv(path: &str) -> Result<std::fs::File> {
+    std::fs::File::open(path).with_context(|| format!("Failed to open {path}"))
+}
+
+/// Like `import_csv`, but reads the files at the given paths.
+pub fn import_csv_files(
+    conn: Arc<Mutex<Connection>>,
+    teebenchargs_path: &str,
+    output_path: &str,
+) -> Result<(usize, usize)> {
+    import_csv(conn, open_csv(teebenchargs_path)?, open_csv(output_path)?)
+}
+
+/// Imports results in the format of `cached/teebenchargs.csv` and `cached/output.csv`, joined by `id` and `teebenchargs_id`.
+///
+/// Runs that are already in the cache with the same arguments and output are skipped, so importing the same files twice does not add anything. Output columns that are not in `OUTPUT_COLUMNS` are ignored. Returns the number of imported and skipped runs.
+pub fn import_csv(
+    conn: Arc<Mutex<Connection>>,
+    teebenchargs: impl std::io::Read,
+    output: impl std::io::Read,
+) -> Result<(usize, usize)> {
+    let conn = conn.lock().unwrap();
+    import_csv_into(&conn, teebenchargs, output)
+}
+
+fn import_csv_into(
+    conn: &Connection,
+    teebenchargs: impl std::io::Read,
+    output: impl std::io::Read,
+) -> Result<(usize, usize)> {
+    // Empty cells of nullable columns stand for NULL, csv has no other way to express it. The csv files have empty cells in other columns as well, they stay empty strings.
+    let cell = |v: &str, nullable: bool| {
+        let v = v.trim();
+        (!(nullable && v.is_empty())).then(|| v.to_string())
+    };
+    let nullable_args = ["r_path", "s_path", "r_size", "s_size"];
+    let mut rdr = csv::Reader::from_reader(teebenchargs);
+    let header: Vec<String> = rdr.headers()?.iter().map(|h| h.trim().to_owned()).collect();
+    let position = |name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .with_context(|| format!("The teebenchargs csv has no column `{name}`"))
+    };
+    let id_idx = position("id")?;
+    let args_idx = CSV_ARGS_COLUMNS
+        .iter()
+        .map(|c| position(c))
+        .collect::<Result<Vec<_>>>()?;
+    let mut args: HashMap<String, Vec<Option<String>>> = HashMap::new();
+    for record in rdr.records() {
+        let record = record?;
+        let values = zip(&args_idx, CSV_ARGS_COLUMNS)
+            .map(|(i, c)| cell(&record[*i], nullable_args.contains(&c)))
+            .collect();
+        args.insert(record[id_idx].trim().to_string(), values);
+    }
+
+    let mut rdr = csv::Reader::from_reader(output);
+    let header: Vec<String> = rdr.headers()?.iter().map(|h| h.trim().to_owned()).collect();
+    let Some(id_idx) = header.iter().position(|h| h == "teebenchargs_id") else {
+        bail!("The output csv has no column `teebenchargs_id`");
+    };
+    let (output_idx, output_columns): (Vec<usize>, Vec<&str>) = header
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| match get_column(h) {
+            Some(_) => true,
+            None => {
+                if *h != "teebenchargs_id" {
+                    warn!("Ignoring unknown column `{h}` of the output csv.");
+                }
+                false
+            }
+        })
+        .map(|(i, h)| (i, h.as_str()))
+        .unzip();
+
+    let conditions = CSV_ARGS_COLUMNS
+        .iter()
+        .map(|c| format!("t.{c}"))
+        .chain(output_columns.iter().map(|c| format!("o.{c}")))
+        .enumerate()
+        .map(|(i, c)| format!("{c} IS ?{}", i + 1))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let exists_sql = format!("SELECT EXISTS(SELECT 1 FROM teebenchargs t JOIN output o ON o.teebenchargs_id = t.id WHERE {conditions})");
+    let insert_args_sql = format!(
+        "INSERT INTO teebenchargs ({}) VALUES ({})",
+        CSV_ARGS_COLUMNS.join(", "),
+        (1..=CSV_ARGS_COLUMNS.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let insert_output_sql = format!(
+        "INSERT INTO output (teebenchargs_id, {}) VALUES ({})",
+        output_columns.join(", "),
+        (1..=output_columns.len() + 1)
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let tx = conn.unchecked_transaction()?;
+    let (mut imported, mut skipped) = (0, 0);
+    {
+        let mut exists = tx.prepare(&exists_sql)?;
+        let mut insert_args = tx.prepare(&insert_args_sql)?;
+        let mut insert_output = tx.prepare(&insert_output_sql)?;
+        for record in rdr.records() {
+            let record = record?;
+            let id = record[id_idx].trim();
+            let Some(args) = args.get(id) else {
+                bail!("The output csv refers to the unknown teebenchargs id {id}");
+            };
+            let output: Vec<Option<String>> = zip(&output_idx, &output_columns)
+                .map(|(i, c)| {
+                    cell(
+                        &record[*i],
+                        get_column(c).map(|c| !c.required).unwrap_or(true),
+                    )
+                })
+                .collect();
+            // The values are all strings, SQLite's type affinity converts them to the column's type. This also applies to the comparisons.
+            let row = args.iter().chain(output.iter());
+            if exists.query_row(rusqlite::params_from_iter(row), |r| r.get::<_, bool>(0))? {
+                skipped += 1;
+                continue;
+            }
+            insert_args.execute(rusqlite::params_from_iter(args))?;
+            let new_id = tx.last_insert_rowid();
+            let new_id = Some(new_id.to_string());
+            insert_output.execute(rusqlite::params_from_iter(
+                std::iter::once(&new_id).chain(output.iter()),
+            ))?;
+            imported += 1;
+        }
+    }
+    tx.commit()?;
+    Ok((imported, skipped))
+}
+
+/// Caches a single TeeBench run, see `insert_experiment_tx`.
 pub fn insert_experiment(
     conn: Arc<Mutex<Connection>>,
     args: TeebenchArgs,
+    algorithm: &str,
+    fingerprint: Option<&str>,
+    source: Option<&str>,
     data: HashMap<String, String>,
+    add_columns: bool,
 ) -> Result<()> {
     let conn = conn.lock().unwrap();
-    conn.execute("INSERT INTO teebenchargs (app_name, dataset, algorithm, threads, selectivity, data_skew, seal_chunk_size, r_tuples, s_tuples, r_path, s_path, r_size, s_size, seal, sort_r, sort_s) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)", (&args.app_name.to_string(), &args.dataset.to_string(), &format!("{:?}", args.algorithm), &args.threads, &args.selectivity, &args.data_skew, &args.seal_chunk_size, &args.r_tuples, &args.s_tuples, &args.r_path, &args.s_path, &args.x, &args.y, &args.seal, &args.sort_r, &args.sort_s))?;
-    let id = conn.last_insert_rowid();
-    conn.execute("INSERT INTO output (teebenchargs_id, algorithm, threads, relR, relS, matches, phase1Cycles, phase2Cycles, cyclesPerTuple, phase1Time, phase2Time, totalTime, throughput, phase1L3CacheMisses, phase1L3HitRatio, phase1L2CacheMisses, phase1L2HitRatio, phase1IPC, phase1IR, phase1EWB, phase1VoluntaryCS, phase1InvoluntaryCS, phase1UserCpuTime, phase1SystemCpuTime, phase2L3CacheMisses, phase2L3HitRatio, phase2L2CacheMisses, phase2L2HitRatio, phase2IPC, phase2IR, phase2EWB, phase2VoluntaryCS, phase2InvoluntaryCS, phase2UserCpuTime, phase2SystemCpuTime, totalL3CacheMisses, totalL3HitRatio, totalL2CacheMisses, totalL2HitRatio, totalIPC, totalIR, totalEWB, totalVoluntaryCS, totalInvoluntaryCS, totalUserCpuTime, totalSystemCpuTime) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45, ?46)", params![
-        id,
-        data["algorithm"],
-        data["threads"],
-        data["relR"],
-        data["relS"],
-        data["matches"],
-        data["phase1Cycles"],
-        data["phase2Cycles"],
-        data["cyclesPerTuple"],
-        data["phase1Time"],
-        data["phase2Time"],
-        data["totalTime"],
-        data["throughput"],
-        data.get("phase1L3CacheMisses"),
-        data.get("phase1L3HitRatio"),
-        data.get("phase1L2CacheMisses"),
-        data.get("phase1L2HitRatio"),
-        data.get("phase1IPC"),
-        data.get("phase1IR"),
-        data.get("phase1EWB"),
-        data.get("phase1VoluntaryCS"),
-        data.get("phase1InvoluntaryCS"),
-        data.get("phase1UserCpuTime"),
-        data.get("phase1SystemCpuTime"),
-        data.get("phase2L3CacheMisses"),
-        data.get("phase2L3HitRatio"),
-        data.get("phase2L2CacheMisses"),
-        data.get("phase2L2HitRatio"),
-        data.get("phase2IPC"),
-        data.get("phase2IR"),
-        data.get("phase2EWB"),
-        data.get("phase2VoluntaryCS"),
-        data.get("phase2InvoluntaryCS"),
-        data.get("phase2UserCpuTime"),
-        data.get("phase2SystemCpuTime"),
-        data.get("totalL3CacheMisses"),
-        data.get("totalL3HitRatio"),
-        data.get("totalL2CacheMisses"),
-        data.get("totalL2HitRatio"),
-        data.get("totalIPC"),
-        data.get("totalIR"),
-        data.get("totalEWB"),
-        data.get("totalVoluntaryCS"),
-        data.get("totalInvoluntaryCS"),
-        data.get("totalUserCpuTime"),
-        data.get("totalSystemCpuTime"),
-    ])?;
+    let tx = conn.unchecked_transaction()?;
+    insert_experiment_tx(
+        &tx,
+        &args,
+        algorithm,
+        fingerprint,
+        source,
+        &data,
+        add_columns,
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Inserts a TeeBench run in `tx`, so several runs can be stored together or not at all.
+///
+/// algorithm: see `algorithm_key`
+///
+/// fingerprint: `BuildFingerprint::hash` of the binaries that produced `data`, `None` if unknown (the result is then only used as historical data)
+///
+/// source: Where the result was measured, `None` for this instance
+///
+/// add_columns: Whether columns that the `output` table doesn't have are added to it. Only for output of TeeBench runs that TeeBenchWeb started, uploads must not change the schema.
+pub fn insert_experiment_tx(
+    tx: &Transaction,
+    args: &TeebenchArgs,
+    algorithm: &str,
+    fingerprint: Option<&str>,
+    source: Option<&str>,
+    data: &HashMap<String, String>,
+    add_columns: bool,
+) -> Result<()> {
+    if let Some(missing) = OUTPUT_COLUMNS
+        .iter()
+        .find(|c| c.required && !data.contains_key(c.name))
+    {
+        bail!("TeeBench output is missing the column `{}`", missing.name);
+    }
+    let existing = table_columns(tx, "output")?;
+    let mut columns = vec![];
+    let mut values = vec![];
+    for (name, value) in data {
+        if !is_valid_column_name(name) {
+            warn!("Ignoring TeeBench output column with invalid name `{name}`.");
+            continue;
+        }
+        if !existing.contains(name) {
+            if !add_columns {
+                bail!("The table `output` has no column `{name}`");
+            }
+            let sql_type = get_column(name)
+                .map(|c| c.sql_type)
+                .unwrap_or_else(|| ColumnType::infer(value));
+            warn!("TeeBench printed the unknown column `{name}`. Adding it to table `output` as {}, consider adding it to `OUTPUT_COLUMNS`.", sql_type.to_sql());
+            // SQLite undoes it together with the insert if the transaction is not committed.
+            tx.execute(
+                &format!("ALTER TABLE output ADD COLUMN {name} {}", sql_type.to_sql()),
+                [],
+            )?;
+        }
+        columns.push(name.as_str());
+        values.push(value);
+    }
+    tx.execute("INSERT INTO teebenchargs (app_name, dataset, algorithm, threads, selectivity, data_skew, seal_chunk_size, r_tuples, s_tuples, r_path, s_path, r_size, s_size, seal, sort_r, sort_s, fingerprint, created, source, profile) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)", params![&args.app_name.to_string(), &args.dataset.to_string(), algorithm, &args.threads, &args.selectivity, &args.data_skew, &args.seal_chunk_size, &args.r_tuples, &args.s_tuples, &args.r_path, &args.s_path, &args.x, &args.y, &args.seal, &args.sort_r, &args.sort_s, fingerprint, OffsetDateTime::now_utc().format(&Rfc3339)?, source, &args.profile])?;
+    let id = tx.last_insert_rowid().to_string();
+    let placeholders = (2..=columns.len() + 1)
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // The values are all strings, SQLite's type affinity converts them to the column's type.
+    tx.execute(
+        &format!(
+            "INSERT INTO output (teebenchargs_id, {}) VALUES (?1, {placeholders})",
+            columns.join(", ")
+        ),
+        rusqlite::params_from_iter(std::iter::once(&id).chain(values)),
+    )?;
     Ok(())
 }
 
@@ -243,16 +816,52 @@ fn query_none<T>(val: &Option<T>, name: &str, idx: usize) -> String {
     }
 }
 
+/// algorithm: see `algorithm_key`
+///
+/// fingerprint: Only return results of this build. `None` also returns results of other builds and the ones without a fingerprint.
+///
+/// Returns up to `limit` results (the repetitions of this run), newest first.
 pub fn search_for_exp(
     conn: Arc<Mutex<Connection>>,
     args: &TeebenchArgs,
-) -> Result<Option<HashMap<String, String>>> {
+    algorithm: &str,
+    fingerprint: Option<&str>,
+    limit: usize,
+) -> Result<Vec<HashMap<String, String>>> {
+    let runs = search_runs(conn, args, algorithm, fingerprint, limit)?;
+    Ok(runs.into_iter().map(|(_, data)| data).collect())
+}
+
+/// Every cached result of a run, of all builds, with the fingerprint of its build. Results without fingerprint are left out.
+pub fn search_cached_runs(
+    conn: Arc<Mutex<Connection>>,
+    args: &TeebenchArgs,
+    algorithm: &str,
+) -> Result<Vec<(String, HashMap<String, String>)>> {
+    let runs = search_runs(conn, args, algorithm, None, i64::MAX as usize)?;
+    Ok(runs
+        .into_iter()
+        .filter_map(|(fingerprint, data)| Some((fingerprint?, data)))
+        .collect())
+}
+
+/// A cached result with the fingerprint of its build, if it has one.
+type FingerprintedResult = (Option<String>, HashMap<String, String>);
+
+/// Like `search_for_exp`, but also returns the fingerprint of each result.
+fn search_runs(
+    conn: Arc<Mutex<Connection>>,
+    args: &TeebenchArgs,
+    algorithm: &str,
+    fingerprint: Option<&str>,
+    limit: usize,
+) -> Result<Vec<FingerprintedResult>> {
     let conn = conn.lock().unwrap();
     debug!("Searching cache for {args:?}...");
     let arg_params = params![
         &args.app_name.to_string(),
         &args.dataset.to_string(),
-        &format!("{:#?}", args.algorithm),
+        algorithm,
         &args.threads,
         &args.selectivity,
         &args.data_skew,
@@ -265,175 +874,314 @@ pub fn search_for_exp(
         &args.y,
         &args.seal,
         &args.sort_r,
-        &args.sort_s
+        &args.sort_s,
+        fingerprint,
+        limit,
+        &args.profile
     ];
-    let sql: &str = &format!("SELECT id FROM teebenchargs WHERE app_name=?1 AND dataset=?2 AND \
+    let sql: &str = &format!("SELECT teebenchargs.fingerprint, output.* FROM output JOIN teebenchargs ON teebenchargs.id = output.teebenchargs_id \
+                                WHERE teebenchargs_id IN (SELECT id FROM teebenchargs \
+                                WHERE app_name=?1 AND dataset=?2 AND \
                                 algorithm=?3 AND threads=?4 AND selectivity=?5 AND data_skew=?6 AND \
                                 seal_chunk_size=?7 AND r_tuples=?8 AND s_tuples=?9 AND {} AND {} \
-                                AND {} AND {} AND seal=?14 AND sort_r=?15 AND sort_s=?16",
+                                AND {} AND {} AND seal=?14 AND sort_r=?15 AND sort_s=?16 \
+                                AND (?17 IS NULL OR fingerprint=?17) AND profile=?19 ORDER BY id DESC LIMIT ?18) \
+                                ORDER BY teebenchargs_id DESC",
                              query_none(&args.r_path, "r_path", 10),
                              query_none(&args.s_path, "s_path", 11),
                              query_none(&args.x, "r_size", 12),
                              query_none(&args.y, "s_size", 13));
 
-    let id = conn.query_row(sql, arg_params, |r| r.get::<usize, usize>(0));
-    let id = match id {
-        Ok(id) => id,
-        Err(rusqlite::Error::QueryReturnedNoRows) => {
-            debug!("Command not found in `teebenchargs`.");
-            return Ok(None);
-        }
-        Err(e) => bail!(e),
-    };
-    let mut map: HashMap<String, String> = HashMap::new();
-    conn.query_row("SELECT algorithm, threads, relR, relS, matches, phase1Cycles, phase2Cycles, cyclesPerTuple, phase1Time, phase2Time, totalTime, throughput, phase1L3CacheMisses, phase1L3HitRatio, phase1L2CacheMisses, phase1L2HitRatio, phase1IPC, phase1IR, phase1EWB, phase1VoluntaryCS, phase1InvoluntaryCS, phase1UserCpuTime, phase1SystemCpuTime, phase2L3CacheMisses, phase2L3HitRatio, phase2L2CacheMisses, phase2L2HitRatio, phase2IPC, phase2IR, phase2EWB, phase2VoluntaryCS, phase2InvoluntaryCS, phase2UserCpuTime, phase2SystemCpuTime, totalL3CacheMisses, totalL3HitRatio, totalL2CacheMisses, totalL2HitRatio, totalIPC, totalIR, totalEWB, totalVoluntaryCS, totalInvoluntaryCS, totalUserCpuTime, totalSystemCpuTime FROM output WHERE teebenchargs_id=(?1)", [id], |r| {
-        map.insert("algorithm".to_owned(), r.get::<usize, String>(0)?);
-        map.insert("threads".to_owned(), r.get::<usize, usize>(1).map(|v| v.to_string())?);
-        map.insert("relR".to_owned(), r.get::<usize, usize>(2).map(|v| v.to_string())?);
-        map.insert("relS".to_owned(), r.get::<usize, usize>(3).map(|v| v.to_string())?);
-        map.insert("matches".to_owned(), r.get::<usize, usize>(4).map(|v| v.to_string())?);
-        map.insert("phase1Cycles".to_owned(), r.get::<usize, usize>(5).map(|v| v.to_string())?);
-        map.insert("phase2Cycles".to_owned(), r.get::<usize, usize>(6).map(|v| v.to_string())?);
-        map.insert("cyclesPerTuple".to_owned(), r.get::<usize, usize>(7).map(|v| v.to_string())?);
-        map.insert("phase1Time".to_owned(), r.get::<usize, usize>(8).map(|v| v.to_string())?);
-        map.insert("phase2Time".to_owned(), r.get::<usize, usize>(9).map(|v| v.to_string())?);
-        map.insert("totalTime".to_owned(), r.get::<usize, usize>(10).map(|v| v.to_string())?);
-        map.insert("throughput".to_owned(), r.get::<usize, f64>(11).map(|v| v.to_string())?);
-        if let Some(val) = r.get::<usize, Option<usize>>(12)? {
-            let val = val.to_string();
-            map.insert("phase1L3CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(13)? {
-            let val = val.to_string();
-            map.insert("phase1L3HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(14)? {
-            let val = val.to_string();
-            map.insert("phase1L2CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(15)? {
-            let val = val.to_string();
-            map.insert("phase1L2HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(16)? {
-            let val = val.to_string();
-            map.insert("phase1IPC".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(17)? {
-            let val = val.to_string();
-            map.insert("phase1IR".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(18)? {
-            let val = val.to_string();
-            map.insert("phase1EWB".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(19)? {
-            let val = val.to_string();
-            map.insert("phase1VoluntaryCS".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(20)? {
-            let val = val.to_string();
-            map.insert("phase1InvoluntaryCS".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(21)? {
-            let val = val.to_string();
-            map.insert("phase1UserCpuTime".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(22)? {
-            let val = val.to_string();
-            map.insert("phase1SystemCpuTime".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(23)? {
-            let val = val.to_string();
-            map.insert("phase2L3CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(24)? {
-            let val = val.to_string();
-            map.insert("phase2L3HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(25)? {
-            let val = val.to_string();
-            map.insert("phase2L2CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(26)? {
-            let val = val.to_string();
-            map.insert("phase2L2HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(27)? {
-            let val = val.to_string();
-            map.insert("phase2IPC".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(28)? {
-            let val = val.to_string();
-            map.insert("phase2IR".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(29)? {
-            let val = val.to_string();
-            map.insert("phase2EWB".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(30)? {
-            let val = val.to_string();
-            map.insert("phase2VoluntaryCS".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(31)? {
-            let val = val.to_string();
-            map.insert("phase2InvoluntaryCS".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(32)? {
-            let val = val.to_string();
-            map.insert("phase2UserCpuTime".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(33)? {
-            let val = val.to_string();
-            map.insert("phase2SystemCpuTime".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(34)? {
-            let val = val.to_string();
-            map.insert("totalL3CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(35)? {
-            let val = val.to_string();
-            map.insert("totalL3HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(36)? {
-            let val = val.to_string();
-            map.insert("totalL2CacheMisses".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(37)? {
-            let val = val.to_string();
-            map.insert("totalL2HitRatio".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<f64>>(38)? {
-            let val = val.to_string();
-            map.insert("totalIPC".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(39)? {
-            let val = val.to_string();
-            map.insert("totalIR".to_owned(), val);
-        }
-        if let Some(val) = r.get::<usize, Option<usize>>(40)? {
-            let val = val.to_string();
-            map.insert("totalEWB".to_owned(), val);
+    let mut stmt = conn.prepare(sql)?;
+    let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let rows = stmt.query_map(arg_params, |r| {
+        let mut map: HashMap<String, String> = HashMap::new();
+        // The first column is the fingerprint.
+        for (i, name) in names.iter().enumerate().skip(1) {
+            if name == "teebenchargs_id" {
+                continue;
+            }
+            let val = match r.get_ref(i)? {
+                ValueRef::Null | ValueRef::Blob(_) => continue,
+                ValueRef::Integer(v) => v.to_string(),
+                ValueRef::Real(v) => v.to_string(),
+                ValueRef::Text(v) => String::from_utf8_lossy(v).into_owned(),
+            };
+            map.insert(name.clone(), val);
         }
-        if let Some(val) = r.get::<usize, Option<usize>>(41)? {
-            let val = val.to_string();
-            map.insert("totalVoluntaryCS".to_owned(), val);
+        Ok((r.get(0)?, map))
+    })?;
+    let results = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    if results.is_empty() {
+        debug!("Command not found in `teebenchargs`.");
+    }
+    Ok(results)
+}
+
+/// Columns of `teebenchargs` that `query_results` returns and filters on.
+const RESULT_ARGS_COLUMNS: [&str; 20] = [
+    "app_name",
+    "dataset",
+    "algorithm",
+    "threads",
+    "selectivity",
+    "data_skew",
+    "seal_chunk_size",
+    "r_tuples",
+    "s_tuples",
+    "r_path",
+    "s_path",
+    "r_size",
+    "s_size",
+    "seal",
+    "sort_r",
+    "sort_s",
+    "fingerprint",
+    "created",
+    "source",
+    "profile",
+];
+
+/// Filter for `query_results`, usually built from the query string of `GET /api/results`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultFilter {
+    /// `(sql condition, parameters)`, the column names are checked against `RESULT_ARGS_COLUMNS`.
+    conditions: Vec<(String, Vec<String>)>,
+    /// Only return these output columns, all if empty.
+    pub measurements: Vec<String>,
+    /// Number of runs (not rows) to skip and return.
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl ResultFilter {
+    /// Every column in `RESULT_ARGS_COLUMNS` can be used as a key:
+    ///
+    /// - `<column>=a,b` matches one of the comma separated values (case insensitive)
+    /// - `min_<column>=x` and `max_<column>=x` match a range, bounds included
+    ///
+    /// `older_than_days=n` matches the runs stored at least `n` days ago. `measurement` is a comma separated list of output columns, `offset` and `limit` page through the runs. Other keys are an error.
+    pub fn from_query(params: &HashMap<String, String>, default_limit: usize) -> Result<Self> {
+        let mut filter = Self {
+            limit: default_limit,
+            ..Default::default()
+        };
+        // Sorted so that the generated SQL does not depend on the order of the HashMap.
+        let mut params: Vec<_> = params.iter().collect();
+        params.sort();
+        for (key, value) in params {
+            match key.as_str() {
+                "measurement" => {
+                    filter.measurements = value.split(',').map(|m| m.trim().to_string()).collect();
+                }
+                "offset" => filter.offset = value.parse().context("Invalid `offset`")?,
+                "limit" => filter.limit = value.parse().context("Invalid `limit`")?,
+                "older_than_days" => {
+                    let days: i64 = value.parse().context("Invalid `older_than_days`")?;
+                    let cutoff = OffsetDateTime::now_utc() - Duration::days(days);
+                    filter
+                        .conditions
+                        .push(("t.created <= ?".to_string(), vec![cutoff.format(&Rfc3339)?]));
+                }
+                key => {
+                    let (column, op) = match (key.strip_prefix("min_"), key.strip_prefix("max_")) {
+                        (Some(column), _) => (column, ">="),
+                        (_, Some(column)) => (column, "<="),
+                        _ => (key, "IN"),
+                    };
+                    if !RESULT_ARGS_COLUMNS.contains(&column) {
+                        bail!("Unknown filter `{key}`");
+                    }
+                    if op == "IN" {
+                        let values: Vec<String> =
+                            value.split(',').map(|v| v.trim().to_string()).collect();
+                        let placeholders = vec!["?"; values.len()].join(", ");
+                        filter.conditions.push((
+                            format!("t.{column} COLLATE NOCASE IN ({placeholders})"),
+                            values,
+                        ));
+                    } else {
+                        filter
+                            .conditions
+                            .push((format!("t.{column} {op} ?"), vec![value.clone()]));
+                    }
+                }
+            }
         }
-        if let Some(val) = r.get::<usize, Option<usize>>(42)? {
-            let val = val.to_string();
-            map.insert("totalInvoluntaryCS".to_owned(), val);
+        Ok(filter)
+    }
+    /// Whether this filter matches all runs.
+    pub fn matches_all(&self) -> bool {
+        self.conditions.is_empty()
+    }
+    /// `WHERE` clause for `teebenchargs t` and its parameters.
+    fn where_clause(&self) -> (String, Vec<&str>) {
+        let mut conditions = vec!["1".to_string()];
+        let mut params: Vec<&str> = vec![];
+        for (condition, values) in &self.conditions {
+            conditions.push(condition.clone());
+            params.extend(values.iter().map(String::as_str));
         }
-        if let Some(val) = r.get::<usize, Option<usize>>(43)? {
-            let val = val.to_string();
-            map.insert("totalUserCpuTime".to_owned(), val);
+        (conditions.join(" AND "), params)
+    }
+}
+
+/// Cached results in long format: One row per run and measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultTable {
+    /// `id` of the run, the `RESULT_ARGS_COLUMNS`, `measurement` and `value`.
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl ResultTable {
+    /// An array with one object per row.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.rows
+            .iter()
+            .map(|row| {
+                serde_json::Value::Object(
+                    self.columns
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().cloned())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+    pub fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        wtr.write_record(&self.columns)?;
+        for row in &self.rows {
+            wtr.write_record(row.iter().map(|v| match v {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s.clone(),
+                v => v.to_string(),
+            }))?;
         }
-        if let Some(val) = r.get::<usize, Option<usize>>(44)? {
-            let val = val.to_string();
-            map.insert("totalSystemCpuTime".to_owned(), val);
+        Ok(String::from_utf8(wtr.into_inner()?)?)
+    }
+}
+
+fn value_to_json(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null | ValueRef::Blob(_) => serde_json::Value::Null,
+        ValueRef::Integer(v) => v.into(),
+        ValueRef::Real(v) => v.into(),
+        ValueRef::Text(v) => String::from_utf8_lossy(v).into(),
+    }
+}
+
+/// All cached runs that match `filter`, oldest first.
+pub fn query_results(conn: Arc<Mutex<Connection>>, filter: &ResultFilter) -> Result<ResultTable> {
+    let conn = conn.lock().unwrap();
+    let (where_clause, params) = filter.where_clause();
+    let args_columns = RESULT_ARGS_COLUMNS
+        .iter()
+        .map(|c| format!("t.{c}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT t.id, {args_columns}, o.* FROM teebenchargs t JOIN output o ON o.teebenchargs_id = t.id \
+         WHERE {where_clause} ORDER BY t.id LIMIT {} OFFSET {}",
+        filter.limit,
+        filter.offset
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    // The output columns follow the id and the arguments.
+    let first_output = RESULT_ARGS_COLUMNS.len() + 1;
+    let output_columns: Vec<String> = stmt.column_names()[first_output..]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+    let mut table = ResultTable {
+        columns: std::iter::once("id")
+            .chain(RESULT_ARGS_COLUMNS)
+            .chain(["measurement", "value"])
+            .map(String::from)
+            .collect(),
+        rows: vec![],
+    };
+    while let Some(row) = rows.next()? {
+        let run: Vec<serde_json::Value> = (0..first_output)
+            .map(|i| row.get_ref(i).map(value_to_json))
+            .collect::<rusqlite::Result<_>>()?;
+        for (i, column) in output_columns.iter().enumerate() {
+            // TeeBench repeats some arguments in its output.
+            if column == "teebenchargs_id" || RESULT_ARGS_COLUMNS.contains(&column.as_str()) {
+                continue;
+            }
+            if !filter.measurements.is_empty() && !filter.measurements.contains(column) {
+                continue;
+            }
+            let value = value_to_json(row.get_ref(first_output + i)?);
+            if value.is_null() {
+                continue;
+            }
+            let mut record = run.clone();
+            record.push(column.clone().into());
+            record.push(value);
+            table.rows.push(record);
         }
-        Ok(())
+    }
+    Ok(table)
+}
+
+/// Deletes all runs that match `filter`, ignoring its limit, offset and measurements. Returns the number of deleted runs.
+pub fn invalidate_results(conn: Arc<Mutex<Connection>>, filter: &ResultFilter) -> Result<usize> {
+    if filter.matches_all() {
+        bail!("Refusing to invalidate the whole cache, add a filter");
+    }
+    let conn = conn.lock().unwrap();
+    let (where_clause, params) = filter.where_clause();
+    let ids = format!("SELECT t.id FROM teebenchargs t WHERE {where_clause}");
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        &format!("DELETE FROM output WHERE teebenchargs_id IN ({ids})"),
+        rusqlite::params_from_iter(&params),
+    )?;
+    let deleted = tx.execute(
+        &format!("DELETE FROM teebenchargs WHERE id IN ({ids})"),
+        rusqlite::params_from_iter(&params),
+    )?;
+    tx.commit()?;
+    info!("Invalidated {deleted} cached run(s).");
+    Ok(deleted)
+}
+
+/// Number of cached runs per platform and algorithm.
+pub fn cache_statistics(conn: Arc<Mutex<Connection>>) -> Result<Vec<CacheStatistics>> {
+    let conn = conn.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "SELECT app_name, algorithm, COUNT(*), COUNT(DISTINCT fingerprint), MIN(created), MAX(created) \
+            FROM teebenchargs GROUP BY app_name, algorithm ORDER BY app_name, algorithm",
+    )?;
+    let rows = stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, usize>(2)?,
+            r.get::<_, usize>(3)?,
+            r.get::<_, Option<String>>(4)?,
+            r.get::<_, Option<String>>(5)?,
+        ))
     })?;
-    Ok(Some(map))
+    let parse = |d: Option<String>| d.map(|d| OffsetDateTime::parse(&d, &Rfc3339)).transpose();
+    let mut statistics = vec![];
+    for row in rows {
+        let (platform, algorithm, runs, builds, oldest, newest) = row?;
+        statistics.push(CacheStatistics {
+            platform,
+            algorithm,
+            runs,
+            builds,
+            oldest: parse(oldest)?,
+            newest: parse(newest)?,
+        });
+    }
+    Ok(statistics)
 }
 
 #[cfg(test)]
@@ -481,6 +1229,290 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_commit_roundtrip() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        migrate(&mut conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        let mut commit = common::hardcoded::predefined_commit();
+        save_commit(conn.clone(), &commit)?;
+        commit.compilation = CompilationStatus::Failed("make: *** Error 1".to_string());
+        commit.profile = BuildProfile {
+            name: "o3".to_string(),
+            native_args: vec!["CFLAGS=-DNATIVE_COMPILATION -O3".to_string()],
+            ..BuildProfile::default()
+        };
+        save_commit(conn.clone(), &commit)?;
+        assert_eq!(load_commits(conn)?, vec![commit]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_job_history() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        migrate(&mut conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        let jobs = common::hardcoded::hardcoded_profiling_jobs();
+        for j in &jobs {
+            save_job(conn.clone(), j)?;
+        }
+        let finished = Job {
+            status: JobStatus::Done {
+                runtime: Duration::seconds(3),
+            },
+            result: Some(common::data_types::JobResult::Compile(Ok("ok".to_string()))),
+            ..jobs[1].clone()
+        };
+        save_job(conn.clone(), &finished)?;
+        delete_waiting_job(conn.clone(), &jobs[0].id)?;
+        delete_waiting_job(conn.clone(), &finished.id)?;
+        // A job that was running when the server stopped waits again.
+        let running = Job {
+            status: JobStatus::Running,
+            ..jobs[2].clone()
+        };
+        save_job(conn.clone(), &running)?;
+        assert_eq!(load_waiting_jobs(conn.clone())?, jobs[2..].to_vec());
+        let page = load_finished_jobs(conn.clone(), Some("Profiling"), 0, 10)?;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.jobs, vec![finished]);
+        assert_eq!(
+            load_finished_jobs(conn.clone(), Some("Compile"), 0, 10)?.total,
+            0
+        );
+        // Low priority jobs come last, the others keep their order.
+        let waiting = load_waiting_jobs(conn.clone())?;
+        save_queue_order(
+            conn.clone(),
+            &[
+                (waiting[0].id, JobPriority::Low),
+                (waiting[1].id, JobPriority::Normal),
+            ],
+        )?;
+        let reordered = load_waiting_jobs(conn)?;
+        assert_eq!(reordered[0].id, waiting[1].id);
+        assert_eq!(reordered.last().unwrap().id, waiting[0].id);
+        assert_eq!(reordered.last().unwrap().priority, JobPriority::Low);
+        Ok(())
+    }
+
+    #[test]
+    fn test_experiment_roundtrip() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        migrate(&mut conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        // Not in the default data from the csv files.
+        let args = TeebenchArgs {
+            threads: 77,
+            ..Default::default()
+        };
+        let mut data: HashMap<String, String> = OUTPUT_COLUMNS
+            .iter()
+            .filter(|c| c.required)
+            .map(|c| {
+                let val = match c.sql_type {
+                    ColumnType::Text => "RHO",
+                    _ => "2",
+                };
+                (c.name.to_string(), val.to_string())
+            })
+            .collect();
+        data.insert("totalEWB".to_string(), "123".to_string());
+        data.insert("newCounter".to_string(), "0.5".to_string());
+        let mut incomplete = data.clone();
+        incomplete.remove("throughput");
+        let alg = format!("{:?}", args.algorithm);
+        assert!(insert_experiment(
+            conn.clone(),
+            args.clone(),
+            &alg,
+            Some("a"),
+            None,
+            incomplete,
+            true
+        )
+        .is_err());
+        // Only output of our own runs adds columns.
+        assert!(insert_experiment(
+            conn.clone(),
+            args.clone(),
+            &alg,
+            Some("a"),
+            None,
+            data.clone(),
+            false
+        )
+        .is_err());
+        insert_experiment(
+            conn.clone(),
+            args.clone(),
+            &alg,
+            Some("a"),
+            None,
+            data.clone(),
+            true,
+        )?;
+        assert_eq!(
+            search_for_exp(conn.clone(), &args, &alg, Some("a"), 1)?,
+            vec![data.clone()]
+        );
+        assert_eq!(
+            search_for_exp(conn.clone(), &args, &alg, Some("b"), 1)?,
+            vec![]
+        );
+        let mut newer = data.clone();
+        newer.insert("throughput".to_string(), "3".to_string());
+        insert_experiment(
+            conn.clone(),
+            args.clone(),
+            &alg,
+            Some("b"),
+            None,
+            newer.clone(),
+            true,
+        )?;
+        assert_eq!(
+            search_for_exp(conn.clone(), &args, &alg, Some("a"), 5)?,
+            vec![data.clone()]
+        );
+        assert_eq!(
+            search_for_exp(conn.clone(), &args, &alg, None, 1)?,
+            vec![newer.clone()]
+        );
+        assert_eq!(
+            search_for_exp(conn, &args, &alg, None, 5)?,
+            vec![newer, data]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_results() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        migrate(&mut conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        let data: HashMap<String, String> = OUTPUT_COLUMNS
+            .iter()
+            .filter(|c| c.required)
+            .map(|c| {
+                let val = match c.sql_type {
+                    ColumnType::Text => "RHO",
+                    _ => "2",
+                };
+                (c.name.to_string(), val.to_string())
+            })
+            .collect();
+        for threads in [77, 78, 79] {
+            let args = TeebenchArgs {
+                threads,
+                ..Default::default()
+            };
+            let alg = format!("{:?}", args.algorithm);
+            insert_experiment(
+                conn.clone(),
+                args,
+                &alg,
+                Some("a"),
+                None,
+                data.clone(),
+                true,
+            )?;
+        }
+        let query = |params: &[(&str, &str)]| {
+            let params = params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            query_results(conn.clone(), &ResultFilter::from_query(&params, 10)?)
+        };
+        let table = query(&[
+            ("min_threads", "78"),
+            ("fingerprint", "A,c"),
+            ("measurement", "throughput"),
+        ])?;
+        let threads = table.columns.iter().position(|c| c == "threads").unwrap();
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][threads], 78);
+        assert_eq!(table.rows[1][threads], 79);
+        assert_eq!(table.rows[0].last(), Some(&serde_json::json!(2.0)));
+        assert_eq!(table.to_json()[1]["measurement"], "throughput");
+        assert_eq!(table.to_csv()?.lines().count(), 3);
+        let table = query(&[("max_threads", "77"), ("fingerprint", "a"), ("limit", "5")])?;
+        assert_eq!(
+            table.rows.len(),
+            data.keys()
+                .filter(|k| k.as_str() != "algorithm" && k.as_str() != "threads")
+                .count()
+        );
+        assert!(query(&[("r_path", "x")])?.rows.is_empty());
+        assert!(query(&[("max_throughput", "1")]).is_err());
+        assert!(query(&[("older_than_days", "1"), ("fingerprint", "a")])?
+            .rows
+            .is_empty());
+        let stats = cache_statistics(conn.clone())?;
+        let ours = stats.iter().find(|s| s.builds == 1).unwrap();
+        assert!(ours.runs >= 3 && ours.newest.is_some());
+        let filter = |params: &[(&str, &str)]| {
+            let params = params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            ResultFilter::from_query(&params, 10)
+        };
+        assert!(invalidate_results(conn.clone(), &filter(&[])?).is_err());
+        let deleted = invalidate_results(
+            conn.clone(),
+            &filter(&[("fingerprint", "a"), ("max_threads", "78")])?,
+        )?;
+        assert_eq!(deleted, 2);
+        assert_eq!(
+            query(&[("fingerprint", "a"), ("measurement", "throughput")])?
+                .rows
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_csv() -> Result<()> {
+        let mut conn = Connection::open_in_memory()?;
+        migrate(&mut conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        // The header and the first two runs.
+        let head = |path: &str| -> Result<String> {
+            let file = std::fs::read_to_string(path)?;
+            Ok(file.lines().take(3).collect::<Vec<_>>().join("\n"))
+        };
+        let teebenchargs = head(TEEBENCHARGS_CSV_PATH)?;
+        let output = head(OUTPUT_CSV_PATH)?;
+        let import =
+            |output: &str| import_csv(conn.clone(), teebenchargs.as_bytes(), output.as_bytes());
+        assert_eq!(import(&output)?, (2, 0));
+        assert_eq!(import(&output)?, (0, 2));
+        assert_eq!(import(&output.replace("18.6183", "18.7"))?, (1, 1));
+        assert!(import("teebenchargs_id,throughput\n9,1.0\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_algorithm_key() -> Result<()> {
+        let (a, b, c) = (
+            Algorithm::Commit(CommitIdType::new_v4()),
+            Algorithm::Commit(CommitIdType::new_v4()),
+            Algorithm::Commit(CommitIdType::new_v4()),
+        );
+        let code = HashMap::from([
+            (a, "int main() {}".to_string()),
+            (b, "int main() {}".to_string()),
+            (c, "int main() { return 1; }".to_string()),
+        ]);
+        assert_eq!(algorithm_key(&a, &code)?, algorithm_key(&b, &code)?);
+        assert_ne!(algorithm_key(&a, &code)?, algorithm_key(&c, &code)?);
+        assert_eq!(algorithm_key(&Algorithm::Rho, &code)?, "Rho");
+        Ok(())
+    }
+
     #[test]
     #[serial]
     fn test_setup_sqlite_wrapper() -> Result<()> {
@@ -1,10 +1,18 @@
 pub const SQLITE_FILE_VAR_NAME: &str = "TEEBENCHWEB_SQLITE_FILE";
 pub const RUN_DIR_VAR_NAME: &str = "TEEBENCHWEB_RUN_DIR";
-/// Set this variable to disable loading default data to the cache
+/// Several TeeBench directories to run independent jobs in parallel, see `run_dirs::parse_run_dirs`. Takes precedence over `TEEBENCHWEB_RUN_DIR`.
+pub const RUN_DIRS_VAR_NAME: &str = "TEEBENCHWEB_RUN_DIRS";
+/// Path to a json file with the build profiles that are offered besides the default one, see `profiles::build_profiles_from_env`.
+pub const BUILD_PROFILES_VAR_NAME: &str = "TEEBENCHWEB_BUILD_PROFILES";
+/// Secret that every `tbw-worker` sends when it registers, see `remote::worker_secret_from_env`. The server and its workers need the same one.
+pub const WORKER_SECRET_VAR_NAME: &str = "TEEBENCHWEB_WORKER_SECRET";
+/// `1` or `0` to say whether SGX is available instead of probing for the SGX device, see `sgx::sgx_available`.
+pub const SGX_VAR_NAME: &str = "TEEBENCHWEB_SGX";
+/// Set this variable to not seed an empty cache with the default data
 pub const EMPTY_CACHE_VAR_NAME: &str = "TEEBENCHWEB_EMPTY_CACHE";
-/// Path to the file that should be read in as default content of the table `teebenchargs`. Relative paths are relative to the `backend` subdirectory.
+/// Path to the file that an empty cache is seeded with (table `teebenchargs`). Relative paths are relative to the `backend` subdirectory.
 pub const TEEBENCHARGS_CSV_PATH: &str = "../cached/teebenchargs.csv";
-/// Path to the file that should be read in as default content of the table `output`. Relative paths are relative to the `backend` subdirectory.
+/// Path to the file that an empty cache is seeded with (table `output`). Relative paths are relative to the `backend` subdirectory.
 pub const OUTPUT_CSV_PATH: &str = "../cached/output.csv";
 
 // TODO Eventually we should evaluate all environment variables first thing after the backend was started, via a function in here, that is called in the main.rs.
@@ -1,9 +1,9 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use common::data_types::{
     Algorithm, Dataset, ExperimentType, FindingStyle, JobConfig, Measurement, Parameter, Platform,
-    Report, UnwrapedExperimentResult, CPU_PHYSICAL_CORES,
+    Report, TeebenchArgs, UnwrapedExperimentResult, CPU_PHYSICAL_CORES,
 };
-use std::iter::zip;
+use common::statistics::TTest;
 use tracing::instrument;
 use tracing::log::info;
 
@@ -12,17 +12,22 @@ pub fn enrich_report_with_findings(jr: &mut Report) -> Result<()> {
     let mut epc_commit_ewb: Vec<i32> = vec![];
     let mut epc_baseline_ewb: Vec<i32> = vec![];
     let mut throughput_improvements: Vec<f32> = vec![];
+    // (threads, improvement in %, commit vs. baseline) for runs with repetitions
+    let mut throughput_tests: Vec<(u8, f32, TTest)> = vec![];
+    // thread counts without enough samples of the commit or baseline to test
+    let mut untested_threads: Vec<u8> = vec![];
 
     // 1. iterate over each experiment chart and enrich it with findings
     for ex in &mut jr.charts {
-        if ex.results.iter().any(|res| res.1.is_err()) {
-            bail!("Some results are errors!");
-        };
+        // Runs that failed or timed out have no numbers, the findings are about the others.
         let results: UnwrapedExperimentResult = ex
             .results
             .iter()
-            .map(|res| (res.0.clone(), res.1.clone().unwrap()))
+            .filter_map(|(args, res)| res.as_ref().ok().map(|r| (args.clone(), r.clone())))
             .collect();
+        if results.is_empty() {
+            continue;
+        }
         match &ex.config {
             JobConfig::Profiling(c) => {
                 match c.measurement {
@@ -459,25 +464,46 @@ pub fn enrich_report_with_findings(jr: &mut Report) -> Result<()> {
                 ExperimentType::Scalability => {
                     let mut tmp = results.clone();
                     tmp.sort_by_key(|(t, _)| t.threads);
-                    let throughput_commit: Vec<f32> = tmp
+                    let throughput_commit: Vec<(&TeebenchArgs, f32)> = tmp
                         .iter()
                         .filter(|(a, _)| match a.algorithm {
                             Algorithm::Commit(_) => true,
                             _ => false,
                         })
-                        .map(|(_, r)| r.get("throughput").unwrap().parse::<f32>().unwrap())
+                        .map(|(a, r)| (a, r.get("throughput").unwrap().parse::<f32>().unwrap()))
                         .collect();
-                    let throughput_baseline: Vec<f32> = tmp
+                    let throughput_baseline: Vec<(&TeebenchArgs, f32)> = tmp
                         .iter()
                         .filter(|(a, _)| match a.algorithm {
                             Algorithm::Commit(_) => false,
                             _ => true,
                         })
-                        .map(|(_, r)| r.get("throughput").unwrap().parse::<f32>().unwrap())
+                        .map(|(a, r)| (a, r.get("throughput").unwrap().parse::<f32>().unwrap()))
                         .collect();
-                    for (a, b) in zip(throughput_commit, throughput_baseline) {
+                    for (args_a, a) in throughput_commit {
+                        // Without the failed runs, the thread counts of both sides may differ.
+                        let Some(&(args_b, b)) = throughput_baseline
+                            .iter()
+                            .find(|(args_b, _)| args_b.threads == args_a.threads)
+                        else {
+                            continue;
+                        };
                         let impr = ((a - b) / b) * 100.0;
-                        throughput_improvements.push(impr);
+                        // Only count differences that are not noise.
+                        match (
+                            ex.statistics_for(args_a, "throughput"),
+                            ex.statistics_for(args_b, "throughput"),
+                        ) {
+                            (Some(sa), Some(sb)) => {
+                                let test = sa.welch_t_test(sb);
+                                info!("throughput t-test for {} threads: {test:?}", args_a.threads);
+                                if test.is_significant() {
+                                    throughput_improvements.push(impr);
+                                }
+                                throughput_tests.push((args_a.threads, impr, test));
+                            }
+                            _ => untested_threads.push(args_a.threads),
+                        }
                     }
                 }
                 ExperimentType::Custom => {}
@@ -582,9 +608,28 @@ pub fn enrich_report_with_findings(jr: &mut Report) -> Result<()> {
         }
         jr.findings.push(common::data_types::Finding {
             title: message,
-            message: "".to_string(),
+            message: describe_throughput_tests(&throughput_tests, true),
             style,
         });
+    } else if !throughput_tests.is_empty() {
+        jr.findings.push(common::data_types::Finding {
+            title: "No significant throughput change".to_string(),
+            message: describe_throughput_tests(&throughput_tests, false),
+            style: FindingStyle::Neutral,
+        });
+    } else if !untested_threads.is_empty() {
+        jr.findings.push(common::data_types::Finding {
+            title: "Not enough samples".to_string(),
+            message: format!(
+                "Throughput changes need at least two samples of the operator and its baseline, use more repetitions ({} threads).",
+                untested_threads
+                    .iter()
+                    .map(u8::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            style: FindingStyle::Neutral,
+        });
     }
 
     // calculate average EPC paging change
@@ -616,3 +661,131 @@ pub fn enrich_report_with_findings(jr: &mut Report) -> Result<()> {
 
     Ok(())
 }
+
+/// Lists the t-tests that are (or, if `significant` is false, are not) significant.
+fn describe_throughput_tests(tests: &[(u8, f32, TTest)], significant: bool) -> String {
+    tests
+        .iter()
+        .filter(|(_, _, t)| t.is_significant() == significant)
+        .map(|(threads, impr, t)| {
+            format!(
+                "{threads} threads: {impr:+.1}% (p = {:.3}, d = {:.2})",
+                t.p_value, t.effect_size
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::commit::CommitIdType;
+    use common::data_types::{ExperimentChart, PerfReportConfig, TeeBenchWebError};
+    use common::statistics::Statistics;
+    use std::collections::HashMap;
+
+    /// A scalability chart of the operator and its baseline with the given throughput samples per
+    /// thread count.
+    fn scalability_report(samples: &[(u8, Vec<f64>, Vec<f64>)]) -> Report {
+        let id = CommitIdType::new_v4();
+        let (config, _) = PerfReportConfig::for_scalability(id, Algorithm::Rho);
+        let mut results = vec![];
+        let mut statistics = vec![];
+        for (threads, commit, baseline) in samples {
+            for (algorithm, values) in [(Algorithm::Commit(id), commit), (Algorithm::Rho, baseline)]
+            {
+                let args = TeebenchArgs::for_scalability(algorithm, Dataset::CacheFit, *threads);
+                let stats = Statistics::new(values.clone());
+                let mean = values.iter().sum::<f64>() / values.len() as f64;
+                results.push((
+                    args.clone(),
+                    Ok(HashMap::from([(
+                        "throughput".to_string(),
+                        mean.to_string(),
+                    )])),
+                ));
+                if let Some(stats) = stats {
+                    statistics.push((args, HashMap::from([("throughput".to_string(), stats)])));
+                }
+            }
+        }
+        let mut chart = ExperimentChart::new(JobConfig::PerfReport(config), results, vec![]);
+        chart.statistics = statistics;
+        Report {
+            charts: vec![chart],
+            findings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_significant_throughput_change() {
+        let mut report = scalability_report(&[
+            (
+                1,
+                vec![120.0, 121.0, 119.5, 120.5],
+                vec![100.0, 101.0, 99.5, 100.5],
+            ),
+            (
+                2,
+                vec![240.0, 242.0, 239.0, 241.0],
+                vec![200.0, 202.0, 199.0, 201.0],
+            ),
+        ]);
+        enrich_report_with_findings(&mut report).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].style, FindingStyle::Good);
+        assert!(report.findings[0].title.starts_with("Throughput increased"));
+    }
+
+    #[test]
+    fn test_throughput_noise() {
+        let mut report = scalability_report(&[
+            (
+                1,
+                vec![101.0, 95.0, 106.0, 98.0],
+                vec![100.0, 104.0, 93.0, 102.0],
+            ),
+            (
+                2,
+                vec![198.0, 212.0, 190.0, 205.0],
+                vec![205.0, 193.0, 210.0, 196.0],
+            ),
+        ]);
+        enrich_report_with_findings(&mut report).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].style, FindingStyle::Neutral);
+        assert_eq!(report.findings[0].title, "No significant throughput change");
+    }
+
+    #[test]
+    fn test_throughput_single_samples() {
+        let mut report = scalability_report(&[(1, vec![150.0], vec![100.0])]);
+        enrich_report_with_findings(&mut report).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].style, FindingStyle::Neutral);
+        assert_eq!(report.findings[0].title, "Not enough samples");
+    }
+
+    #[test]
+    fn test_failed_runs_are_skipped() {
+        let mut report = scalability_report(&[
+            (
+                1,
+                vec![120.0, 121.0, 119.5, 120.5],
+                vec![100.0, 101.0, 99.5, 100.5],
+            ),
+            (
+                2,
+                vec![240.0, 242.0, 239.0, 241.0],
+                vec![200.0, 202.0, 199.0, 201.0],
+            ),
+        ]);
+        // The operator timed out with one thread.
+        report.charts[0].results[0].1 = Err(TeeBenchWebError::Timeout);
+        enrich_report_with_findings(&mut report).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].style, FindingStyle::Good);
+        assert!(report.findings[0].message.starts_with("2 threads"));
+    }
+}
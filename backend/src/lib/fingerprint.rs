@@ -0,0 +1,137 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env::var;
+use std::path::Path;
+use tokio::process::Command as TokioCommand;
+use tracing::{info, warn};
+
+use common::data_types::BuildProfile;
+
+use crate::{profile_dir, REPLACE_FILE};
+
+/// Written next to the binaries in `$TEEBENCHWEB_RUN_DIR/bin` (or the `profile_dir`) by `compile`.
+pub(crate) const FINGERPRINT_FILE: &str = "fingerprint.json";
+
+/// Everything besides the operator's code that influences TeeBench's results.
+///
+/// The operator's code is part of the algorithm key (see `caching::algorithm_key`), so `REPLACE_FILE` is ignored here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildFingerprint {
+    /// `git rev-parse HEAD` of `$TEEBENCHWEB_RUN_DIR`.
+    pub revision: String,
+    /// Hash of the uncommitted changes, if there are any.
+    pub local_changes: Option<String>,
+    /// The `make` arguments of the build profile.
+    pub flags: String,
+    /// First line of `$CXX --version`, or of the compiler that the profile sets with `CXX=`.
+    pub compiler: String,
+}
+
+impl BuildFingerprint {
+    /// Fingerprints the TeeBench sources as they are now, built with `profile`.
+    pub async fn of_sources(tee_bench_dir: &Path, profile: &BuildProfile) -> Self {
+        let revision = match git(tee_bench_dir, &["rev-parse", "HEAD"]).await {
+            Ok(r) => r.trim().to_string(),
+            Err(e) => {
+                warn!("Cannot determine the TeeBench revision: {e:#}");
+                "unknown".to_string()
+            }
+        };
+        let exclude = format!(":(exclude){REPLACE_FILE}");
+        let local_changes = match git(tee_bench_dir, &["diff", "HEAD", "--", ".", &exclude]).await {
+            Ok(diff) if diff.is_empty() => None,
+            Ok(diff) => Some(short_hash([diff.as_str()])),
+            Err(_) => None,
+        };
+        let make_args = [profile.native_make_args(), profile.sgx_make_args()].concat();
+        let flags = make_args.join(" ");
+        let cxx = match make_args.iter().rev().find_map(|a| a.strip_prefix("CXX=")) {
+            Some(cxx) => cxx.to_string(),
+            None => var("CXX").unwrap_or_else(|_| "g++".to_string()),
+        };
+        let compiler = match TokioCommand::new(&cxx).arg("--version").output().await {
+            Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            _ => {
+                warn!("Cannot determine the version of `{cxx}`");
+                "unknown".to_string()
+            }
+        };
+        Self {
+            revision,
+            local_changes,
+            flags,
+            compiler,
+        }
+    }
+
+    /// Fingerprint of the binaries of `profile` currently in `$TEEBENCHWEB_RUN_DIR`, see `profile_dir`.
+    ///
+    /// Falls back to the sources if they were not built by `compile`.
+    pub async fn of_binaries(tee_bench_dir: &Path, profile: &BuildProfile) -> Self {
+        let path = profile_dir(tee_bench_dir, profile).join(FINGERPRINT_FILE);
+        match tokio::fs::read(&path).await {
+            Ok(json) => match serde_json::from_slice(&json) {
+                Ok(fp) => return fp,
+                Err(e) => warn!("Ignoring invalid {path:?}: {e}"),
+            },
+            Err(_) => info!("No {path:?}, fingerprinting the TeeBench sources instead."),
+        }
+        Self::of_sources(tee_bench_dir, profile).await
+    }
+
+    /// Fingerprint of the binaries in `dir`, eg. the binaries of an operator that `compile` kept. `None` if there is none.
+    pub async fn in_dir(dir: &Path) -> Option<Self> {
+        let json = tokio::fs::read(dir.join(FINGERPRINT_FILE)).await.ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+
+    /// Stores this fingerprint with the binaries in `dir`, see `of_binaries` and `in_dir`.
+    pub async fn write(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(FINGERPRINT_FILE);
+        tokio::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to write {path:?}"))
+    }
+
+    /// Short identifier that is stored with every cached result.
+    pub fn hash(&self) -> String {
+        short_hash([
+            self.revision.as_str(),
+            self.local_changes.as_deref().unwrap_or_default(),
+            self.flags.as_str(),
+            self.compiler.as_str(),
+        ])
+    }
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let out = TokioCommand::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !out.status.success() {
+        bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+fn short_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+    let hash = format!("{:x}", hasher.finalize());
+    hash[..16].to_string()
+}
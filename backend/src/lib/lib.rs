@@ -1,40 +1,150 @@
-mod caching;
+mod build_dir;
+pub mod caching;
 mod config;
 mod findings;
+mod fingerprint;
+pub mod profiles;
+pub mod remote;
+pub mod run_dirs;
+pub mod sgx;
 
 use anyhow::{bail, Context, Result};
+use futures_util::FutureExt;
 use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::VecDeque;
 use std::collections::{HashMap, HashSet};
-use std::env::var;
-use std::path::PathBuf;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 use time::Instant;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command as TokioCommand;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use common::commandline::Commandline;
-use common::commit::{CommitState, CompilationStatus, PerfReportStatus};
+use common::commit::{Commit, CommitIdType, CommitState, CompilationStatus, PerfReportStatus};
 use common::data_types::{
-    Algorithm, ExperimentChart, Job, JobConfig, JobIdType, JobResult, JobStatus, Report,
+    Algorithm, BuildProfile, ExperimentChart, Job, JobConfig, JobIdType, JobResult, JobStatus,
+    OutputLine, Platform, Report, ResultUpload, RunEvent, RunProgress, SingleRunResult,
     TeeBenchWebError, REPLACE_ALG,
 };
 use common::hardcoded::{hardcoded_perf_report_commands, hardcoded_perf_report_configs};
+use common::output_schema::{get_column, OUTPUT_COLUMNS};
+use common::queue::enqueue;
+use common::statistics::aggregate;
 
-use caching::{search_for_exp, setup_sqlite};
+use caching::{algorithm_key, search_cached_runs, search_for_exp};
 
-use crate::caching::insert_experiment;
-use crate::config::RUN_DIR_VAR_NAME;
+use crate::build_dir::{remove_stale_builds, BuildDir};
+use crate::caching::{insert_experiment, insert_experiment_tx, save_build, save_commit, save_job};
 use crate::findings::enrich_report_with_findings;
+use crate::fingerprint::{BuildFingerprint, FINGERPRINT_FILE};
+use crate::remote::{CachedRun, FromWorker, RemoteWorker, ToWorker};
+use crate::run_dirs::{run_dirs_from_env, RunDir};
+use crate::sgx::sgx_available;
 
 const BIN_FOLDER: &str = "bin";
+/// Files in `bin` that `compile` keeps for each operator, see `artifact_dir`. The fingerprint comes last, so an incomplete copy is never used.
+const ARTIFACTS: [&str; 5] = [
+    "native",
+    "sgx",
+    "enclave.signed.so",
+    "lib/pcm/libpcm.so",
+    FINGERPRINT_FILE,
+];
+/// Files of the simulation build in the `sim` folder next to the `ARTIFACTS`, see `compile_sim`.
+const SIM_ARTIFACTS: [&str; 4] = [
+    "sgx-sim",
+    "enclave.signed.so",
+    "lib/pcm/libpcm.so",
+    FINGERPRINT_FILE,
+];
 const REPLACE_FILE: &str = "Joins/TBW/OperatorJoin.cpp";
 
+/// Limit of `search_for_exp` that returns every cached sample.
+pub(crate) const ALL_SAMPLES: usize = i64::MAX as usize;
+
+/// Finished jobs are reported by tasks that run in parallel, but handled one at a time.
+const DONE_CHANNEL_SIZE: usize = 16;
+
 type SwitchedInType = Arc<tokio::sync::Mutex<Option<Algorithm>>>;
-pub type CancelNotifierType = bool;
-pub type PartialReportType = (JobIdType, Report);
+/// The job to cancel, or `None` to cancel all running jobs.
+pub type CancelNotifierType = Option<JobIdType>;
+/// Number of run directories that `build_baseline` is building right now. Profiling jobs are refused until it is 0.
+pub type BaselineBuildsType = Arc<AtomicUsize>;
+/// Whether a run directory or a connected worker has SGX, see `Executor::sgx`.
+pub type SgxExecutorsType = Arc<AtomicBool>;
+
+/// Whether `profiling_task` starts waiting jobs, see `ClientMessage::PauseQueue`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueMode {
+    #[default]
+    Running,
+    /// No job starts, running jobs finish.
+    Paused,
+    /// No job starts, running jobs were stopped and wait in the queue again.
+    Preempted,
+}
+
+/// Progress of a running job that is relayed to the clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JobProgress {
+    /// The report so far, see `ServerMessage::PartialReport`.
+    Report(Report),
+    Run(RunProgress),
+    /// New lines of the build of an operator, see `ServerMessage::CompilerOutput`.
+    CompilerOutput(CommitIdType, Vec<OutputLine>),
+}
+pub type JobProgressType = (JobIdType, JobProgress);
+
+/// Applies `f` to the commit with `id` and writes the changed commit through to the database.
+///
+/// Returns `None` if there is no such commit.
+fn update_commit<R>(
+    commits: &Arc<Mutex<CommitState>>,
+    conn: &Arc<Mutex<Connection>>,
+    id: &CommitIdType,
+    f: impl FnOnce(&mut Commit) -> R,
+) -> Option<R> {
+    let (res, commit) = {
+        let mut guard = commits.lock().unwrap();
+        let c = guard.get_by_id_mut(id)?;
+        (f(c), c.clone())
+    };
+    if let Err(e) = save_commit(conn.clone(), &commit) {
+        error!("Failed to persist commit {id}: {e:#}");
+    }
+    Some(res)
+}
+
+/// Identifies an operator's code, see `caching::algorithm_key`. It is the `build_hash` of the default profile.
+pub fn operator_hash(code: &str) -> String {
+    build_hash(code, &BuildProfile::default())
+}
+
+/// Identifies an operator's code together with the flags of `profile` it is built with.
+fn build_hash(code: &str, profile: &BuildProfile) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(code);
+    for arg in profile
+        .native_make_args()
+        .iter()
+        .chain(profile.sgx_make_args().iter())
+    {
+        hasher.update([0]);
+        hasher.update(arg);
+    }
+    let hash = format!("{:x}", hasher.finalize());
+    hash[..32].to_string()
+}
 
 fn display_command_output(o: &std::process::Output, cmd: String) -> String {
     let mut res = String::new();
@@ -53,6 +163,155 @@ fn display_command_output(o: &std::process::Output, cmd: String) -> String {
     res
 }
 
+/// Runs `cmd` like `Command::output`, but also sends every line it prints to `log` right away.
+///
+/// Stdout and stderr are read at the same time, so their lines arrive in `log` in the order they were printed.
+async fn output_logged(
+    cmd: &mut TokioCommand,
+    cmd_string: &str,
+    log: Option<&mpsc::UnboundedSender<OutputLine>>,
+) -> std::io::Result<std::process::Output> {
+    let Some(log) = log else {
+        return cmd.output().await;
+    };
+    let _ = log.send(OutputLine::Command(cmd_string.to_string()));
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdout_reader = BufReader::new(child.stdout.take().unwrap());
+    let mut stderr_reader = BufReader::new(child.stderr.take().unwrap());
+    let (mut stdout, mut stderr) = (vec![], vec![]);
+    let (mut stdout_line, mut stderr_line) = (vec![], vec![]);
+    let (mut stdout_done, mut stderr_done) = (false, false);
+    let line = |buf: &[u8]| String::from_utf8_lossy(buf).trim_end().to_string();
+    while !(stdout_done && stderr_done) {
+        // A line that was only read partially stays in its buffer, and is completed by the next call. At the end of the stream, the buffer holds the last line if it has no newline.
+        tokio::select! {
+            n = stdout_reader.read_until(b'\n', &mut stdout_line), if !stdout_done => {
+                stdout_done = n? == 0;
+                if !stdout_line.is_empty() {
+                    let _ = log.send(OutputLine::Stdout(line(&stdout_line)));
+                    stdout.append(&mut stdout_line);
+                }
+            }
+            n = stderr_reader.read_until(b'\n', &mut stderr_line), if !stderr_done => {
+                stderr_done = n? == 0;
+                if !stderr_line.is_empty() {
+                    let _ = log.send(OutputLine::Stderr(line(&stderr_line)));
+                    stderr.append(&mut stderr_line);
+                }
+            }
+        }
+    }
+    let status = child.wait().await?;
+    Ok(std::process::Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Sends the lines of `log` to the clients until it closes. While the clients can't keep up, lines are collected instead of dropped.
+async fn forward_compiler_output(
+    id: CommitIdType,
+    mut log: mpsc::UnboundedReceiver<OutputLine>,
+    job_id: JobIdType,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
+) {
+    let mut pending = vec![];
+    while let Some(line) = log.recv().await {
+        pending.push(line);
+        while let Ok(line) = log.try_recv() {
+            pending.push(line);
+        }
+        let lines = std::mem::take(&mut pending);
+        let sent = partial_results_sender
+            .lock()
+            .await
+            .try_send((job_id, JobProgress::CompilerOutput(id, lines)));
+        if let Err(e) = sent {
+            if let (_, JobProgress::CompilerOutput(_, lines)) = e.into_inner() {
+                pending = lines;
+            }
+        }
+    }
+    if !pending.is_empty() {
+        send_progress(
+            &partial_results_sender,
+            job_id,
+            JobProgress::CompilerOutput(id, pending),
+        )
+        .await;
+    }
+}
+
+/// Where `compile` keeps the binaries of the operator with `code`, built with `profile`: `bin/<build hash>/`.
+fn artifact_dir(tee_bench_dir: &Path, code: &str, profile: &BuildProfile) -> PathBuf {
+    tee_bench_dir
+        .join(BIN_FOLDER)
+        .join(build_hash(code, profile))
+}
+
+/// Where the stock algorithms run from with `profile`: `bin` for the default profile, `bin/profile-<name>/` for the others.
+pub(crate) fn profile_dir(tee_bench_dir: &Path, profile: &BuildProfile) -> PathBuf {
+    let bin = tee_bench_dir.join(BIN_FOLDER);
+    match profile.is_default() {
+        true => bin,
+        false => bin.join(format!("profile-{}", profile.name)),
+    }
+}
+
+/// Copies the binaries of a successful build in `build_dir` to the `profile_dir`, where jobs use them, and to the `artifact_dir` of `code`, so running the operator again doesn't rebuild it.
+///
+/// The stock operator (`code` is `None`) only goes to the `profile_dir`.
+async fn promote_artifacts(
+    build_dir: &Path,
+    tee_bench_dir: &Path,
+    code: Option<&str>,
+    profile: &BuildProfile,
+) -> Result<()> {
+    let built = build_dir.join(BIN_FOLDER);
+    let dirs = code
+        .map(|code| artifact_dir(tee_bench_dir, code, profile))
+        .into_iter()
+        .chain([profile_dir(tee_bench_dir, profile)]);
+    for dir in dirs {
+        copy_artifacts(&built, &dir, &ARTIFACTS).await?;
+    }
+    Ok(())
+}
+
+/// Copies `files` from `src` to `dest`. The fingerprint in `dest` is removed first, and `files` end with it, so an incomplete copy is never used.
+async fn copy_artifacts(src: &Path, dest: &Path, files: &[&str]) -> Result<()> {
+    let _ = tokio::fs::remove_file(dest.join(FINGERPRINT_FILE)).await;
+    for file in files {
+        let to = dest.join(file);
+        if let Some(parent) = to.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {parent:?}"))?;
+        }
+        tokio::fs::copy(src.join(file), &to)
+            .await
+            .with_context(|| format!("Failed to copy {file} to {to:?}"))?;
+    }
+    Ok(())
+}
+
+/// Whether `dir` holds all `files` of an operator, built as the TeeBench build with `fingerprint`.
+async fn has_artifacts(dir: &Path, files: &[&str], fingerprint: &str) -> bool {
+    for file in files {
+        if !tokio::fs::try_exists(dir.join(file)).await.unwrap_or(false) {
+            return false;
+        }
+    }
+    BuildFingerprint::in_dir(dir)
+        .await
+        .is_some_and(|f| f.hash() == fingerprint)
+}
+
 async fn make_clean(tbw_dir: &PathBuf) -> Result<()> {
     let clean_out = TokioCommand::new("make")
         .kill_on_drop(true)
@@ -70,41 +329,43 @@ async fn make_clean(tbw_dir: &PathBuf) -> Result<()> {
 // TODO Rewrite with a better Command library, eg. duct (if that works for async?). Problems:
 // - Commands that return nonzero exit status don't return an Err. The returned result is whether it could even start the command. Makes my code more complicated.
 // - Nicer way to get the actual string representing the command for logging.
-// - Nicer way to get the interleaved output of stdin and stderr (not the function `display_command_output`!). Might be a problem when compiling SGX: CXX <= File is printed to stdout, compiler errors to stderr.
-#[instrument(skip(code_hashmap))]
+// - Nicer way to get the interleaved output of stdin and stderr (not the function `display_command_output`!). Might be a problem when compiling SGX: CXX <= File is printed to stdout, compiler errors to stderr. Only `log` has them interleaved.
+/// Builds the operator `code` with `profile` in a copy of `tee_bench_dir`, or the stock `REPLACE_FILE` if it is `None`. Only if that succeeds, the binaries are copied to the `profile_dir`. Every line the build prints is also sent to `log`.
+#[instrument(skip(code, log))]
 async fn compile(
-    alg: &Algorithm,
     tee_bench_dir: &PathBuf,
-    code_hashmap: HashMap<Algorithm, String>,
+    code: Option<&str>,
+    profile: &BuildProfile,
+    log: Option<&mpsc::UnboundedSender<OutputLine>>,
 ) -> Result<String> {
     let mut output = String::new();
-    let new_code = code_hashmap.get(alg).unwrap();
-    let mut replace_file_path = tee_bench_dir.clone();
-    replace_file_path.push(REPLACE_FILE);
-    tokio::fs::write(replace_file_path, new_code)
-        .await
-        .with_context(|| format!("Failed to write new operator to {REPLACE_FILE}"))?;
+    let fingerprint = BuildFingerprint::of_sources(tee_bench_dir, profile).await;
+    let build = BuildDir::new(tee_bench_dir).await?;
+    let build_dir = &build.path;
+    if let Some(code) = code {
+        build
+            .replace_file(REPLACE_FILE, code)
+            .await
+            .with_context(|| format!("Failed to write new operator to {REPLACE_FILE}"))?;
+    }
     // let compile_args_sgx = ["-B", "sgx"];
-    let compile_args_native = ["native", "CFLAGS=-DNATIVE_COMPILATION"];
-    let compile_args_sgx = [
-        "sgx",
-        "SGX_DEBUG=1",
-        "SGX_PRERELEASE=0",
-        "SGX_MODE=HW",
-        "CFLAGS=-DPCM_COUNT -DSGX_COUNTERS",
-    ];
+    let compile_args_native = profile.native_make_args();
+    let compile_args_sgx = profile.sgx_make_args();
     //let compile_args_native = ["native", "CFLAGS='-DPCM_COUNT -DSGX_COUNTERS'"];
     let compile_args_native_joined = compile_args_native.join(" ");
     let compile_args_sgx_joined = compile_args_sgx.join(" ");
     let enclave_name = "enclave.signed.so";
-    make_clean(&tee_bench_dir).await?;
-    let make_out = TokioCommand::new("make")
-        .kill_on_drop(true)
-        .current_dir(tee_bench_dir)
-        .args(compile_args_native)
-        .output()
-        .await
-        .with_context(|| format!("Failed to run `make {compile_args_native_joined}`"))?;
+    make_clean(build_dir).await?;
+    let make_out = output_logged(
+        TokioCommand::new("make")
+            .kill_on_drop(true)
+            .current_dir(build_dir)
+            .args(&compile_args_native),
+        &format!("make {compile_args_native_joined}"),
+        log,
+    )
+    .await
+    .with_context(|| format!("Failed to run `make {compile_args_native_joined}`"))?;
     output.push_str(&display_command_output(
         &make_out,
         format!("make {compile_args_native_joined}"),
@@ -112,9 +373,8 @@ async fn compile(
     if !make_out.status.success() {
         bail!("Failed to compile native version:\n{output}");
     }
-    let (mut app_path, mut bin_path) = (tee_bench_dir.clone(), tee_bench_dir.clone());
-    let (mut libpcm_path_dest, mut libpcm_path_src) =
-        (tee_bench_dir.clone(), tee_bench_dir.clone());
+    let (mut app_path, mut bin_path) = (build_dir.clone(), build_dir.clone());
+    let (mut libpcm_path_dest, mut libpcm_path_src) = (build_dir.clone(), build_dir.clone());
     bin_path.push(BIN_FOLDER);
     tokio::fs::create_dir_all(&bin_path)
         .await
@@ -136,13 +396,16 @@ async fn compile(
         .await
         .with_context(|| format!("Failed to copy native binary to {bin_path:?}!"))?;
     bin_path.pop();
-    let cmd_out = TokioCommand::new("./native")
-        .kill_on_drop(true)
-        .args(&["-a", REPLACE_ALG])
-        .current_dir(&bin_path)
-        .output()
-        .await
-        .with_context(|| format!("Failed to run `./native -a {REPLACE_ALG}`"))?;
+    let cmd_out = output_logged(
+        TokioCommand::new("./native")
+            .kill_on_drop(true)
+            .args(&["-a", REPLACE_ALG])
+            .current_dir(&bin_path),
+        &format!("./native -a {REPLACE_ALG}"),
+        log,
+    )
+    .await
+    .with_context(|| format!("Failed to run `./native -a {REPLACE_ALG}`"))?;
     output.push_str(&display_command_output(
         &cmd_out,
         format!("./native -a {REPLACE_ALG}"),
@@ -150,14 +413,17 @@ async fn compile(
     if !cmd_out.status.success() {
         bail!("Running native example failed with:\n{output}");
     }
-    make_clean(&tee_bench_dir).await?;
-    let cmd_out = TokioCommand::new("make")
-        .kill_on_drop(true)
-        .current_dir(tee_bench_dir)
-        .args(compile_args_sgx)
-        .output()
-        .await
-        .with_context(|| format!("Failed to run `make {compile_args_sgx_joined}`"))?;
+    make_clean(build_dir).await?;
+    let cmd_out = output_logged(
+        TokioCommand::new("make")
+            .kill_on_drop(true)
+            .current_dir(build_dir)
+            .args(&compile_args_sgx),
+        &format!("make {compile_args_sgx_joined}"),
+        log,
+    )
+    .await
+    .with_context(|| format!("Failed to run `make {compile_args_sgx_joined}`"))?;
     output.push_str(&display_command_output(
         &cmd_out,
         format!("make {compile_args_sgx_joined}"),
@@ -179,23 +445,167 @@ async fn compile(
         .await
         .with_context(|| format!("Failed to copy {enclave_name} over!"))?;
     bin_path.pop();
-    let cmd_out = TokioCommand::new("./sgx")
-        .kill_on_drop(true)
-        .args(&["-a", REPLACE_ALG])
-        .current_dir(&bin_path)
-        .output()
+    // The enclave is still built without SGX, so the binaries are the same on every machine. It just cannot be tried out.
+    if sgx_available() {
+        let cmd_out = output_logged(
+            TokioCommand::new("./sgx")
+                .kill_on_drop(true)
+                .args(&["-a", REPLACE_ALG])
+                .current_dir(&bin_path),
+            &format!("./sgx -a {REPLACE_ALG}"),
+            log,
+        )
         .await
         .with_context(|| format!("Failed to run ./sgx -a {REPLACE_ALG}"))?;
+        output.push_str(&display_command_output(
+            &cmd_out,
+            format!("./sgx -a {REPLACE_ALG}"),
+        ));
+        if !cmd_out.status.success() {
+            bail!("Running SGX example failed with:\n{output}");
+        }
+    } else {
+        output.push_str(&format!(
+            "SGX is not available, skipped `./sgx -a {REPLACE_ALG}`.\n"
+        ));
+    }
+    fingerprint.write(&build_dir.join(BIN_FOLDER)).await?;
+    promote_artifacts(build_dir, tee_bench_dir, code, profile).await?;
+    info!(
+        "Built TeeBench {} ({} profile): {fingerprint:?}",
+        fingerprint.hash(),
+        profile.name
+    );
+    Ok(output)
+}
+
+/// Builds the SGX version of the operator `code` (the stock one if `None`) in simulation mode, the profile's SGX build with `SGX_MODE=SIM`, in a copy of `tee_bench_dir`. Only if that succeeds, the `SIM_ARTIFACTS` are copied to `sim_dir`.
+///
+/// Unlike `compile`, it only runs the first time an operator is run on `Platform::SgxSim`.
+#[instrument(skip(code))]
+async fn compile_sim(
+    tee_bench_dir: &PathBuf,
+    code: Option<&str>,
+    profile: &BuildProfile,
+    sim_dir: &Path,
+) -> Result<String> {
+    let mut output = String::new();
+    let fingerprint = BuildFingerprint::of_sources(tee_bench_dir, profile).await;
+    let build = BuildDir::new(tee_bench_dir).await?;
+    let build_dir = &build.path;
+    if let Some(code) = code {
+        build
+            .replace_file(REPLACE_FILE, code)
+            .await
+            .with_context(|| format!("Failed to write new operator to {REPLACE_FILE}"))?;
+    }
+    let compile_args_sim = profile.sgx_sim_make_args();
+    let compile_args_sim_joined = compile_args_sim.join(" ");
+    make_clean(build_dir).await?;
+    let cmd_out = output_logged(
+        TokioCommand::new("make")
+            .kill_on_drop(true)
+            .current_dir(build_dir)
+            .args(&compile_args_sim),
+        &format!("make {compile_args_sim_joined}"),
+        None,
+    )
+    .await
+    .with_context(|| format!("Failed to run `make {compile_args_sim_joined}`"))?;
+    output.push_str(&display_command_output(
+        &cmd_out,
+        format!("make {compile_args_sim_joined}"),
+    ));
+    if !cmd_out.status.success() {
+        bail!("Failed to compile SGX simulation version:\n{output}");
+    }
+    // The simulation app loads its enclave from the working directory too, so it gets a folder of its own, see `Platform::bin_subdir`.
+    let enclave_name = "enclave.signed.so";
+    let sim_path = build_dir
+        .join(BIN_FOLDER)
+        .join(Platform::SgxSim.bin_subdir().unwrap());
+    for (src, dest) in [
+        ("app", "sgx-sim"),
+        (enclave_name, enclave_name),
+        ("lib/pcm/libpcm.so", "lib/pcm/libpcm.so"),
+    ] {
+        let (src, dest) = (build_dir.join(src), sim_path.join(dest));
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {parent:?}!"))?;
+        }
+        debug!("Copying from {src:?} to {dest:?}");
+        tokio::fs::copy(&src, &dest)
+            .await
+            .with_context(|| format!("Failed to copy {src:?} to {dest:?}!"))?;
+    }
+    let cmd_out = output_logged(
+        TokioCommand::new("./sgx-sim")
+            .kill_on_drop(true)
+            .args(["-a", REPLACE_ALG])
+            .current_dir(&sim_path),
+        &format!("./sgx-sim -a {REPLACE_ALG}"),
+        None,
+    )
+    .await
+    .with_context(|| format!("Failed to run ./sgx-sim -a {REPLACE_ALG}"))?;
     output.push_str(&display_command_output(
         &cmd_out,
-        format!("./sgx -a {REPLACE_ALG}"),
+        format!("./sgx-sim -a {REPLACE_ALG}"),
     ));
     if !cmd_out.status.success() {
-        bail!("Running SGX example failed with:\n{output}");
+        bail!("Running SGX simulation example failed with:\n{output}");
     }
+    fingerprint.write(&sim_path).await?;
+    copy_artifacts(&sim_path, sim_dir, &SIM_ARTIFACTS).await?;
+    info!(
+        "Built the SGX simulation of TeeBench {} ({} profile) in {sim_dir:?}",
+        fingerprint.hash(),
+        profile.name
+    );
     Ok(output)
 }
 
+/// Whether `bin` lacks binaries or holds binaries of other TeeBench sources than `tee_bench_dir` has now.
+async fn needs_baseline_build(tee_bench_dir: &Path) -> bool {
+    let bin = tee_bench_dir.join(BIN_FOLDER);
+    for file in ["native", "sgx", "enclave.signed.so"] {
+        if !tokio::fs::try_exists(bin.join(file)).await.unwrap_or(false) {
+            info!("{:?} is missing.", bin.join(file));
+            return true;
+        }
+    }
+    let sources = BuildFingerprint::of_sources(tee_bench_dir, &BuildProfile::default()).await;
+    match BuildFingerprint::in_dir(&bin).await {
+        Some(built) if built == sources => false,
+        Some(built) => {
+            info!("{bin:?} is outdated, it was built as {built:?}.");
+            true
+        }
+        None => {
+            info!("{bin:?} was not built by TeeBenchWeb.");
+            true
+        }
+    }
+}
+
+/// Builds TeeBench with the stock operator into `bin` if it is missing or outdated, see `needs_baseline_build`.
+///
+/// Without it, nothing can run until an operator is compiled. Returns whether it built.
+///
+/// Runs before anything else uses the run directory, so it also removes the copies of builds that were interrupted, see `BuildDir`.
+pub async fn build_baseline(tee_bench_dir: &PathBuf) -> Result<bool> {
+    remove_stale_builds(tee_bench_dir).await;
+    if !needs_baseline_build(tee_bench_dir).await {
+        return Ok(false);
+    }
+    info!("Building TeeBench with the stock operator in {tee_bench_dir:?}.");
+    let output = compile(tee_bench_dir, None, &BuildProfile::default(), None).await?;
+    trace!("Compiler output: {output}");
+    Ok(true)
+}
+
 #[instrument(skip(out))]
 fn parse_output(out: Vec<u8>) -> Result<HashMap<String, String>> {
     let mut rdr = csv::Reader::from_reader(&*out);
@@ -208,91 +618,262 @@ fn parse_output(out: Vec<u8>) -> Result<HashMap<String, String>> {
     Ok(exp_result)
 }
 
+/// Caches the results of TeeBench runs outside of TeeBenchWeb. Returns the number of imported runs.
+///
+/// The runs are stored without fingerprint, so they are only used as historical data. Nothing is imported if one of the runs is invalid or fails to be stored. Columns that are not in `OUTPUT_COLUMNS` are refused, uploads never change the schema of the cache.
+pub fn import_teebench_output(
+    conn: Arc<Mutex<Connection>>,
+    upload: &ResultUpload,
+    commits: &CommitState,
+) -> Result<usize> {
+    let source = upload.source.trim();
+    if source.is_empty() {
+        bail!("The source of the results is missing");
+    }
+    let mut cmd = Commandline::parse(&upload.command)?;
+    if cmd.algorithm.is_commit() {
+        let Some(id) = upload.commit else {
+            bail!("Results of `-a {REPLACE_ALG}` need the commit of the operator that ran");
+        };
+        cmd.algorithm = Algorithm::Commit(id);
+    }
+    let code_hashmap = commits.get_used_code(&HashSet::from([cmd.algorithm]));
+    let alg_key = algorithm_key(&cmd.algorithm, &code_hashmap)?;
+    let args = cmd.to_teebench_args();
+    let mut rdr = csv::Reader::from_reader(upload.csv.as_bytes());
+    let mut runs = vec![];
+    for (i, run) in rdr.deserialize::<HashMap<String, String>>().enumerate() {
+        let run = run.with_context(|| format!("Invalid csv in run {}", i + 1))?;
+        // Concatenated outputs repeat the header.
+        if run.iter().all(|(k, v)| k == v) {
+            continue;
+        }
+        if let Some(missing) = OUTPUT_COLUMNS
+            .iter()
+            .find(|c| c.required && !run.contains_key(c.name))
+        {
+            bail!("Run {} is missing the column `{}`", i + 1, missing.name);
+        }
+        if let Some(unknown) = run.keys().find(|k| get_column(k).is_none()) {
+            bail!("Run {} has the unknown column `{unknown}`", i + 1);
+        }
+        if let Some(threads) = run.get("threads") {
+            if threads.trim() != args.threads.to_string() {
+                bail!(
+                    "Run {} used {threads} threads, but the command line specifies {}",
+                    i + 1,
+                    args.threads
+                );
+            }
+        }
+        runs.push(run);
+    }
+    if runs.is_empty() {
+        bail!("The csv contains no runs");
+    }
+    let conn = conn.lock().unwrap();
+    let tx = conn.unchecked_transaction()?;
+    for (i, run) in runs.iter().enumerate() {
+        insert_experiment_tx(&tx, &args, &alg_key, None, Some(source), run, false)
+            .with_context(|| format!("Failed to store run {}", i + 1))?;
+    }
+    tx.commit()?;
+    info!("Imported {} run(s) of `{cmd}` from {source}.", runs.len());
+    Ok(runs.len())
+}
+
 async fn run_teebench(
     cmd: &Commandline,
-    switched_in: SwitchedInType,
-    mut tee_bench_dir: PathBuf,
+    run_dir: &RunDir,
     code_hashmap: HashMap<Algorithm, String>,
     cmd_string: String,
-    cmd_tasks: &mut Vec<(
-        common::data_types::TeebenchArgs,
-        Result<HashMap<String, String>, TeeBenchWebError>,
-    )>,
     args_key: common::data_types::TeebenchArgs,
     errors: &mut bool,
     conn: Arc<Mutex<Connection>>,
-) -> Result<()> {
-    let mut switched_in = switched_in.lock().await;
-    if cmd.algorithm.is_commit()
-        && (switched_in.is_none()
-            || (switched_in.is_some() && switched_in.unwrap() != cmd.algorithm))
-    {
-        info!("Compiling: {cmd:?}, switched in : {switched_in:?}");
-        match compile(&cmd.algorithm, &tee_bench_dir, code_hashmap).await {
+    fingerprint: &str,
+    timeout: Option<Duration>,
+) -> Result<SingleRunResult> {
+    let alg_key = algorithm_key(&cmd.algorithm, &code_hashmap)?;
+    let profile = &cmd.profile;
+    let mut switched_in = run_dir.switched_in.lock().await;
+    // Operators run from the binaries `compile` kept for them, or from `bin` right after they were compiled. `switched_in` only tracks `bin`, the other profiles always run from kept binaries.
+    let mut bin_dir = profile_dir(&run_dir.path, profile);
+    let code = code_hashmap
+        .get(&cmd.algorithm)
+        .filter(|_| cmd.algorithm.is_commit());
+    // The simulation runs from a build of its own, see `compile_sim`.
+    let needs_build = cmd.app != Platform::SgxSim
+        && match code {
+            Some(code) => {
+                let dir = artifact_dir(&run_dir.path, code, profile);
+                if has_artifacts(&dir, &ARTIFACTS, fingerprint).await {
+                    bin_dir = dir;
+                    false
+                } else {
+                    !profile.is_default() || *switched_in != Some(cmd.algorithm)
+                }
+            }
+            None => {
+                !profile.is_default() && !has_artifacts(&bin_dir, &ARTIFACTS, fingerprint).await
+            }
+        };
+    if needs_build {
+        info!(
+            "Compiling: {cmd:?} with the {} profile, switched in : {switched_in:?}",
+            profile.name
+        );
+        match compile(&run_dir.path, code.map(String::as_str), profile, None).await {
             Ok(o) => trace!("Compiler output: {o}"),
             Err(e) => {
                 error!("Error while switching in code and compiling commit for experiment:\n{e:#}");
                 bail!("Failed to compile");
             }
         }
-        switched_in.replace(cmd.algorithm);
+        match (code, profile.is_default()) {
+            (Some(code), false) => bin_dir = artifact_dir(&run_dir.path, code, profile),
+            (Some(_), true) => {
+                switched_in.replace(cmd.algorithm);
+            }
+            (None, _) => (),
+        }
     }
-    tee_bench_dir.push(BIN_FOLDER);
-    info!("Running `{cmd_string}` (alg: {:?})", cmd.algorithm);
-    let output = to_command(cmd)
-        .current_dir(tee_bench_dir)
-        .output()
-        .await
-        .expect("Failed to run TeeBench");
+    if cmd.app == Platform::SgxSim {
+        // It is kept next to the binaries of the operator, and only built once it runs.
+        let sim_dir = match code {
+            Some(code) => artifact_dir(&run_dir.path, code, profile),
+            None => bin_dir,
+        }
+        .join(Platform::SgxSim.bin_subdir().unwrap());
+        if !has_artifacts(&sim_dir, &SIM_ARTIFACTS, fingerprint).await {
+            info!(
+                "Compiling the SGX simulation: {cmd:?} with the {} profile",
+                profile.name
+            );
+            match compile_sim(&run_dir.path, code.map(String::as_str), profile, &sim_dir).await {
+                Ok(o) => trace!("Compiler output: {o}"),
+                Err(e) => {
+                    error!("Error while compiling the SGX simulation for experiment:\n{e:#}");
+                    bail!("Failed to compile");
+                }
+            }
+        }
+        bin_dir = sim_dir;
+    }
+    info!(
+        "Running `{cmd_string}` (alg: {:?}) in {:?}",
+        cmd.algorithm, bin_dir
+    );
+    // The command is killed when `output` is dropped after the timeout.
+    let output = to_command(cmd, run_dir.cpus.as_deref())
+        .current_dir(&bin_dir)
+        .output();
+    let output = match timeout {
+        Some(t) => match tokio::time::timeout(t, output).await {
+            Ok(output) => output,
+            Err(_) => {
+                warn!("Killed `{cmd_string}` after {} s.", t.as_secs());
+                return Ok(Err(TeeBenchWebError::Timeout));
+            }
+        },
+        None => output.await,
+    };
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            error!("Failed to run `{cmd_string}` in {bin_dir:?}: {e}");
+            *errors = true;
+            return Ok(Err(TeeBenchWebError::TeeBenchCrash(format!(
+                "Failed to run `{cmd_string}`: {e}"
+            ))));
+        }
+    };
     if !output.status.success() {
         error!("Command {cmd_string} failed with {output:#?}");
-        cmd_tasks.push((
-            args_key,
-            Err(TeeBenchWebError::TeeBenchCrash(format!(
-                "Command `{cmd_string}` failed with:\n{output:#?}"
-            ))),
-        ));
         *errors = true;
-        bail!("Failed to run teebench!");
+        Ok(Err(TeeBenchWebError::TeeBenchCrash(format!(
+            "Command `{cmd_string}` failed with:\n{output:#?}"
+        ))))
     } else {
         let human_readable = String::from_utf8(output.stdout.clone()).unwrap();
         trace!("Task output:\n```\n{human_readable}\n```");
         let data = parse_output(output.stdout);
         match data {
             Ok(results) => {
-                insert_experiment(conn.clone(), args_key.clone(), results.clone()).unwrap();
-                cmd_tasks.push((args_key, Ok(results)));
+                if let Err(e) = insert_experiment(
+                    conn.clone(),
+                    args_key.clone(),
+                    &alg_key,
+                    Some(fingerprint),
+                    None,
+                    results.clone(),
+                    true,
+                ) {
+                    error!("Failed to cache the result of `{cmd_string}`: {e:#}");
+                }
+                if let Some(results_tx) = &run_dir.results_tx {
+                    let _ = results_tx.send(CachedRun {
+                        args: args_key,
+                        algorithm: alg_key,
+                        fingerprint: fingerprint.to_string(),
+                        data: results.clone(),
+                    });
+                }
+                Ok(Ok(results))
             }
             Err(e) => {
                 warn!("Failed to parse output: {e}");
-                cmd_tasks.push((args_key, Err(TeeBenchWebError::Unknown)));
+                Ok(Err(TeeBenchWebError::Unknown))
             }
         }
     }
-    Ok(())
+}
+
+/// Time the next TeeBench run may take: the timeout of a single run, cut short by what is left until the `deadline` of the job. Zero if the job is out of time.
+fn remaining_time(
+    run_timeout: Option<Duration>,
+    deadline: Option<tokio::time::Instant>,
+) -> Option<Duration> {
+    let left = deadline.map(|d| d.saturating_duration_since(tokio::time::Instant::now()));
+    match (run_timeout, left) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Relays progress of a job to the clients. It is dropped rather than holding up the job when nobody receives it.
+async fn send_progress(
+    partial_results_sender: &Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
+    job_id: JobIdType,
+    progress: JobProgress,
+) {
+    if let Err(e) = partial_results_sender
+        .lock()
+        .await
+        .try_send((job_id, progress))
+    {
+        debug!("Dropped progress of job {job_id:?}: {e}");
+    }
 }
 
 // Showing `switched_in` with tracing seems to be wrong. It is always shown as empty, but the code doesn't run like it is.
 #[instrument(skip(
-    tee_bench_dir,
+    run_dir,
     commits,
     configs,
     cmds,
     code_hashmap,
-    switched_in,
     conn,
     partial_results_sender,
     job_id
 ))]
 async fn run_experiment(
-    tee_bench_dir: PathBuf,
+    run_dir: &RunDir,
     commits: Arc<Mutex<CommitState>>,
     configs: Vec<JobConfig>,
     cmds: Vec<Vec<Commandline>>,
     code_hashmap: HashMap<Algorithm, String>,
-    switched_in: SwitchedInType,
     conn: Arc<Mutex<Connection>>,
-    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<PartialReportType>>>,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
     job_id: JobIdType,
 ) -> JobResult {
     let mut errors = false;
@@ -300,81 +881,202 @@ async fn run_experiment(
         charts: vec![],
         findings: vec![],
     };
-    let mut iter = cmds.iter().zip(configs).peekable();
-    while let Some((chart_cmds, conf)) = iter.next() {
+    // Fingerprint hashes by profile name, determined when a profile is first used.
+    let mut fingerprints: HashMap<String, String> = HashMap::new();
+    // All configs of a job have the same timeouts.
+    let deadline = configs
+        .first()
+        .and_then(|c| c.timeouts().1)
+        .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
+    let total = cmds
+        .iter()
+        .flatten()
+        .filter(|c| !c.to_teebench_args().crkj_mway_wrong_thread_count())
+        .count();
+    let mut run = 0;
+    for (chart_cmds, conf) in cmds.iter().zip(configs) {
         if errors {
             break;
         }
+        let repetitions = conf.repetitions().max(1) as usize;
+        let run_timeout = conf.timeouts().0.map(Duration::from_secs);
         let mut cmd_tasks: Vec<(
             common::data_types::TeebenchArgs,
             Result<HashMap<String, String>, _>,
         )> = vec![];
+        let mut statistics = vec![];
         for cmd in chart_cmds {
             let args_key = cmd.to_teebench_args();
             let cmd_string = format!("{cmd}");
             if args_key.crkj_mway_wrong_thread_count() {
                 continue;
             }
-            match search_for_exp(conn.clone(), &args_key) {
-                Ok(Some(r)) => {
-                    info!(
-                        "Found cached result for `{cmd_string}` (alg: {:?})",
-                        cmd.algorithm
-                    );
-                    cmd_tasks.push((args_key, Ok(r)));
+            // Every run counted in `total` sends an event, so that the progress reaches it.
+            run += 1;
+            let progress = |event| {
+                JobProgress::Run(RunProgress {
+                    run,
+                    total,
+                    command: cmd_string.clone(),
+                    event,
+                })
+            };
+            let alg_key = match algorithm_key(&cmd.algorithm, &code_hashmap) {
+                Ok(k) => k,
+                Err(e) => {
+                    error!("Skipping `{cmd_string}`: {e:#}");
+                    let error = TeeBenchWebError::Compile(format!("{e:#}"));
+                    let event = RunEvent::Failed {
+                        duration: time::Duration::ZERO,
+                        error,
+                    };
+                    send_progress(&partial_results_sender, job_id, progress(event)).await;
                     continue;
                 }
-                Ok(None) => {
-                    match run_teebench(
-                        cmd,
-                        switched_in.clone(),
-                        tee_bench_dir.clone(),
-                        code_hashmap.clone(),
-                        cmd_string,
-                        &mut cmd_tasks,
-                        args_key,
-                        &mut errors,
-                        conn.clone(),
+            };
+            if !fingerprints.contains_key(&cmd.profile.name) {
+                let fingerprint = BuildFingerprint::of_binaries(&run_dir.path, &cmd.profile).await;
+                if let Err(e) = save_build(conn.clone(), &fingerprint) {
+                    error!("Failed to save the build fingerprint: {e:#}");
+                }
+                fingerprints.insert(cmd.profile.name.clone(), fingerprint.hash());
+            }
+            let fingerprint = &fingerprints[&cmd.profile.name];
+            let fingerprint_filter = if conf.include_historical() {
+                None
+            } else {
+                Some(fingerprint.as_str())
+            };
+            let mut samples = match search_for_exp(
+                conn.clone(),
+                &args_key,
+                &alg_key,
+                fingerprint_filter,
+                repetitions,
+            ) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    error!("Searching the cache failed with: {e}");
+                    let event = RunEvent::Failed {
+                        duration: time::Duration::ZERO,
+                        error: TeeBenchWebError::NoResults,
+                    };
+                    send_progress(&partial_results_sender, job_id, progress(event)).await;
+                    continue;
+                }
+            };
+            if !samples.is_empty() {
+                info!(
+                    "Found {} cached result(s) for `{cmd_string}` (alg: {:?})",
+                    samples.len(),
+                    cmd.algorithm
+                );
+            }
+            let started = Instant::now();
+            let event = if samples.len() < repetitions {
+                RunEvent::Started
+            } else {
+                RunEvent::Cached
+            };
+            send_progress(&partial_results_sender, job_id, progress(event.clone())).await;
+            let mut failure = None;
+            while samples.len() < repetitions {
+                let timeout = match remaining_time(run_timeout, deadline) {
+                    Some(t) if t.is_zero() => {
+                        warn!("Not running `{cmd_string}`, the job ran out of time.");
+                        failure = Some(TeeBenchWebError::Timeout);
+                        break;
+                    }
+                    t => t,
+                };
+                match run_teebench(
+                    cmd,
+                    run_dir,
+                    code_hashmap.clone(),
+                    cmd_string.clone(),
+                    args_key.clone(),
+                    &mut errors,
+                    conn.clone(),
+                    fingerprint,
+                    timeout,
+                )
+                .await
+                {
+                    Ok(Ok(r)) => samples.push(r),
+                    Ok(Err(e)) => {
+                        failure = Some(e);
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("Error running TeeBench: {e}");
+                        failure = Some(TeeBenchWebError::Compile(format!("{e:#}")));
+                        break;
+                    }
+                }
+            }
+            let duration = started.elapsed();
+            if let Some(error) = failure {
+                send_progress(
+                    &partial_results_sender,
+                    job_id,
+                    progress(RunEvent::Failed {
+                        duration,
+                        error: error.clone(),
+                    }),
+                )
+                .await;
+                cmd_tasks.push((args_key, Err(error)));
+            } else {
+                if event == RunEvent::Started {
+                    send_progress(
+                        &partial_results_sender,
+                        job_id,
+                        progress(RunEvent::Finished { duration }),
                     )
-                    .await
-                    {
-                        Ok(()) => (),
-                        Err(e) => warn!("Error running TeeBench: {e}"),
+                    .await;
+                }
+                // Performance reports test the operator against its baseline with every cached
+                // sample of the build, not just the repetitions of this job.
+                if matches!(conf, JobConfig::PerfReport(_)) {
+                    match search_for_exp(
+                        conn.clone(),
+                        &args_key,
+                        &alg_key,
+                        fingerprint_filter,
+                        ALL_SAMPLES,
+                    ) {
+                        Ok(all) if all.len() > samples.len() => samples = all,
+                        Ok(_) => (),
+                        Err(e) => error!("Searching the cache failed with: {e}"),
+                    }
+                }
+                if let Some((result, stats)) = aggregate(&samples) {
+                    if !stats.is_empty() {
+                        statistics.push((args_key.clone(), stats));
                     }
+                    cmd_tasks.push((args_key, Ok(result)));
                 }
-                Err(e) => error!("Searching the cache failed with: {e}"),
             }
+            // Send the chart so far, so it fills in point by point.
+            let mut partial_report = report.clone();
+            let mut chart = ExperimentChart::new(conf.clone(), cmd_tasks.clone(), vec![]);
+            chart.statistics = statistics.clone();
+            partial_report.charts.push(chart);
+            send_progress(
+                &partial_results_sender,
+                job_id,
+                JobProgress::Report(partial_report),
+            )
+            .await;
         }
-        let experiment_chart = ExperimentChart::new(conf.clone(), cmd_tasks, vec![]);
+        let mut experiment_chart = ExperimentChart::new(conf.clone(), cmd_tasks, vec![]);
+        experiment_chart.statistics = statistics;
+        debug!("Finished chart: {}", experiment_chart.info());
         report.charts.push(experiment_chart);
-        if iter.peek().is_some() {
-            let partial_results_sender = partial_results_sender.lock().await;
-            partial_results_sender
-                .send((job_id, report.clone()))
-                .await
-                .unwrap();
-            let infos = report
-                .charts
-                .iter()
-                .map(|c| c.info())
-                .fold(String::new(), |mut a, b| {
-                    a.reserve(b.len());
-                    a.push_str(&b);
-                    a
-                });
-            info!(
-                "Sent partial Report: {} chart(s):\n{}",
-                report.charts.len(),
-                infos
-            );
-        }
-        {
-            let mut commits = commits.lock().unwrap();
-            if let JobConfig::PerfReport(common::data_types::PerfReportConfig { id, .. }) = conf {
-                if let Some(c) = commits.get_by_id_mut(&id) {
-                    c.report = Some(JobResult::Exp(Ok(report.clone())));
-                }
-            }
+        if let JobConfig::PerfReport(common::data_types::PerfReportConfig { id, .. }) = conf {
+            update_commit(&commits, &conn, &id, |c| {
+                c.report = Some(JobResult::Exp(Ok(report.clone())));
+            });
         }
     }
 
@@ -386,36 +1088,28 @@ async fn run_experiment(
     JobResult::Exp(Ok(report))
 }
 
-#[instrument(skip(conf, commits, currently_switched_in, conn, partial_results_sender))]
+#[instrument(skip(conf, run_dir, commits, conn, partial_results_sender))]
 async fn runner(
     conf: JobConfig,
     job_id: JobIdType,
+    run_dir: &RunDir,
     commits: Arc<Mutex<CommitState>>,
-    currently_switched_in: SwitchedInType,
     conn: Arc<Mutex<Connection>>,
-    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<PartialReportType>>>,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
 ) -> JobResult {
-    let tee_bench_dir = PathBuf::from(
-        var(RUN_DIR_VAR_NAME).unwrap_or_else(|_| panic!("{RUN_DIR_VAR_NAME} not set")),
-    );
-    // TODO Move to JobConfig method.
-    let algs = match conf {
-        JobConfig::Profiling(ref c) => c.algorithms.clone(),
-        JobConfig::PerfReport(ref c) => {
-            if let Algorithm::Commit(_) = c.baseline {
-                HashSet::from([c.baseline, Algorithm::Commit(c.id)])
-            } else {
-                HashSet::from([Algorithm::Commit(c.id)])
-            }
-        }
-        JobConfig::Compile(id) => HashSet::from([Algorithm::Commit(id)]),
-    };
     let code_hashmap = {
         let guard = commits.lock().unwrap();
-        guard.get_used_code(&algs)
+        guard.get_used_code(&conf.used_algorithms())
     };
     match conf {
         JobConfig::Profiling(ref c) => {
+            let mut c = c.clone();
+            if !sgx_available() && c.platforms.remove(&Platform::Sgx) {
+                warn!("SGX is not available, leaving out the SGX runs of job {job_id:?}.");
+                if c.platforms.is_empty() {
+                    return JobResult::Exp(Err(TeeBenchWebError::SgxUnavailable));
+                }
+            }
             let cmds = c.to_teebench_cmd();
             let configs = c
                 .datasets
@@ -428,12 +1122,11 @@ async fn runner(
                 })
                 .collect();
             run_experiment(
-                tee_bench_dir,
+                run_dir,
                 commits,
                 configs,
                 cmds,
                 code_hashmap,
-                currently_switched_in,
                 conn,
                 partial_results_sender,
                 job_id,
@@ -441,243 +1134,803 @@ async fn runner(
             .await
         }
         JobConfig::PerfReport(ref pr_conf) => {
-            let baseline = || {
-                let mut guard = commits.lock().unwrap();
-                if let Some(mut c) = guard.get_by_id_mut(&pr_conf.id) {
-                    c.perf_report_running = PerfReportStatus::Running(job_id);
-                    return (c.baseline, c.title.clone(), c.version.clone());
+            let (baseline, mut cmds) = update_commit(&commits, &conn, &pr_conf.id, |c| {
+                c.perf_report_running = PerfReportStatus::Running(job_id);
+                (c.baseline, perf_report_commands(c))
+            })
+            .expect("Could not find the commit!");
+            let mut configs = hardcoded_perf_report_configs(pr_conf.id, baseline);
+            for c in configs.iter_mut() {
+                if let JobConfig::PerfReport(c) = c {
+                    c.repetitions = pr_conf.repetitions;
+                    c.run_timeout = pr_conf.run_timeout;
+                    c.job_timeout = pr_conf.job_timeout;
                 }
-                panic!("Could not find the commit!");
-            };
-            let (baseline, commit_title, commit_version) = baseline();
-            let cmds = hardcoded_perf_report_commands(
-                pr_conf.id,
-                &baseline,
-                &commit_title,
-                &commit_version,
-            );
-            let configs = hardcoded_perf_report_configs(pr_conf.id, baseline);
+            }
+            if !sgx_available() {
+                warn!(
+                    "SGX is not available, the performance report of {:?} only has native results.",
+                    pr_conf.id
+                );
+                (cmds, configs) = cmds
+                    .into_iter()
+                    .zip(configs)
+                    .filter_map(|(chart, mut conf)| {
+                        let chart: Vec<_> = chart
+                            .into_iter()
+                            .filter(|c| c.app != Platform::Sgx)
+                            .collect();
+                        if let JobConfig::PerfReport(c) = &mut conf {
+                            c.native_only = true;
+                        }
+                        (!chart.is_empty()).then_some((chart, conf))
+                    })
+                    .unzip();
+            }
             let results = run_experiment(
-                tee_bench_dir,
+                run_dir,
                 commits.clone(),
                 configs,
                 cmds,
                 code_hashmap,
-                currently_switched_in,
-                conn,
+                conn.clone(),
                 partial_results_sender,
                 job_id,
             )
             .await;
-            {
-                let mut guard = commits.lock().unwrap();
-                if let Some(mut c) = guard.get_by_id_mut(&pr_conf.id) {
-                    c.report = Some(results.clone());
-                    c.perf_report_running = if results.is_ok() {
-                        PerfReportStatus::Successful
-                    } else {
-                        PerfReportStatus::Failed
-                    };
-                }
-            }
+            update_commit(&commits, &conn, &pr_conf.id, |c| {
+                c.report = Some(results.clone());
+                c.perf_report_running = if results.is_ok() {
+                    PerfReportStatus::Successful
+                } else {
+                    PerfReportStatus::Failed
+                };
+            });
             // To make adding perf report data to the static website easier, I'll just write them all to files. Just remove them from time to time ;)
             // let json = serde_json::to_string_pretty(&serde_json::json!(results)).unwrap();
             // std::fs::write(format!("../cached/temp_report_{:?}.json", pr_conf.id), json).unwrap();
             results
         }
         JobConfig::Compile(ref id) => {
-            {
-                let mut guard = commits.lock().unwrap();
-                if let Some(mut c) = guard.get_by_id_mut(id) {
-                    c.compilation = CompilationStatus::Compiling;
-                }
-            }
-            let result = JobResult::Compile(
-                compile(&Algorithm::Commit(*id), &tee_bench_dir, code_hashmap)
+            update_commit(&commits, &conn, id, |c| {
+                c.compilation = CompilationStatus::Compiling;
+            });
+            let profile = commits
+                .lock()
+                .unwrap()
+                .get_by_id(id)
+                .map(|c| c.profile.clone())
+                .unwrap_or_default();
+            let mut switched_in = run_dir.switched_in.lock().await;
+            let (log_tx, log_rx) = mpsc::unbounded_channel();
+            let forwarder = tokio::spawn(forward_compiler_output(
+                *id,
+                log_rx,
+                job_id,
+                partial_results_sender,
+            ));
+            let result = JobResult::Compile(match code_hashmap.get(&Algorithm::Commit(*id)) {
+                Some(code) => compile(&run_dir.path, Some(code), &profile, Some(&log_tx))
                     .await
                     .map_err(|e| e.to_string()),
-            );
-            {
-                let mut guard = commits.lock().unwrap();
-                if let Some(mut c) = guard.get_by_id_mut(id) {
-                    c.compilation = match result {
-                        JobResult::Compile(Ok(ref msg)) => {
-                            CompilationStatus::Successful(msg.clone())
-                        }
-                        JobResult::Compile(Err(ref msg)) => CompilationStatus::Failed(msg.clone()),
-                        _ => unreachable!(),
-                    };
-                }
+                None => Err(format!("Operator {id:?} does not exist")),
+            });
+            drop(log_tx);
+            let _ = forwarder.await;
+            // A failed build leaves `bin` as it was. Other profiles don't touch `bin`.
+            if result.is_ok() && profile.is_default() {
+                *switched_in = Some(Algorithm::Commit(*id));
             }
+            drop(switched_in);
+            update_commit(&commits, &conn, id, |c| {
+                c.compilation = match result {
+                    JobResult::Compile(Ok(ref msg)) => CompilationStatus::Successful(msg.clone()),
+                    JobResult::Compile(Err(ref msg)) => CompilationStatus::Failed(msg.clone()),
+                    _ => unreachable!(),
+                };
+            });
             result
         }
     }
 }
 
-#[instrument(skip(
-    queue,
-    queue_tx,
-    commits,
-    currently_switched_in,
-    conn,
-    partial_results_sender
-))]
-async fn work_on_queue(
+/// Like `runner`, but a panic fails the job instead of leaving its executor busy forever.
+async fn guarded_runner(
+    conf: JobConfig,
+    job_id: JobIdType,
+    run_dir: &RunDir,
+    commits: Arc<Mutex<CommitState>>,
+    conn: Arc<Mutex<Connection>>,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
+) -> JobResult {
+    let result = AssertUnwindSafe(runner(
+        conf.clone(),
+        job_id,
+        run_dir,
+        commits.clone(),
+        conn.clone(),
+        partial_results_sender,
+    ))
+    .catch_unwind()
+    .await;
+    if let Ok(result) = result {
+        return result;
+    }
+    error!("Job {job_id:?} panicked.");
+    // Don't leave the operator in the state the job set at its start.
+    match conf {
+        JobConfig::Compile(id) => {
+            let msg = "TeeBenchWeb crashed while compiling".to_string();
+            update_commit(&commits, &conn, &id, |c| {
+                c.compilation = CompilationStatus::Failed(msg.clone());
+            });
+            JobResult::Compile(Err(msg))
+        }
+        JobConfig::PerfReport(pr_conf) => {
+            update_commit(&commits, &conn, &pr_conf.id, |c| {
+                c.perf_report_running = PerfReportStatus::Failed;
+            });
+            JobResult::Exp(Err(TeeBenchWebError::Unknown))
+        }
+        JobConfig::Profiling(_) => JobResult::Exp(Err(TeeBenchWebError::Unknown)),
+    }
+}
+
+/// Everything a job needs besides where it runs, shared by all concurrently running jobs.
+#[derive(Clone)]
+struct JobContext {
     queue: Arc<Mutex<VecDeque<Job>>>,
     queue_tx: mpsc::Sender<Job>,
     commits: Arc<Mutex<CommitState>>,
-    currently_switched_in: SwitchedInType,
     conn: Arc<Mutex<Connection>>,
-    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<PartialReportType>>>,
-) {
-    fn peek_queue(queue: Arc<Mutex<VecDeque<Job>>>) -> Option<Job> {
-        let guard = queue.lock().unwrap();
-        guard.front().map(Job::clone)
-    }
-    // TODO Do not clone the queue. It should be always the same arc, but how to make it work with the borrow checker?
-    // Probably by making the above function a closure, which would also be more idiomatic.
-    while let Some(current_job) = peek_queue(queue.clone()) {
-        info!("Working on {current_job:#?}...");
-        let now = Instant::now();
-        let result = runner(
-            current_job.config.clone(),
-            current_job.id,
-            commits.clone(),
-            currently_switched_in.clone(),
-            conn.clone(),
-            partial_results_sender.clone(),
-        )
-        .await;
-        let runtime = now.elapsed();
-        let result_type = if result.is_ok() {
-            "Success".to_string()
-        } else {
-            format!("Failure: {result:?}")
-        };
-        info!("Process completed: {result_type}.");
-        let finished_job = Job {
-            status: JobStatus::Done { runtime },
-            result: Some(result),
-            ..current_job
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
+    /// Notifies `profiling_task` that the job with this id is finished and its executor is free again.
+    done_tx: mpsc::Sender<JobIdType>,
+}
+
+/// Where a job runs: in a local run directory or on a connected `tbw-worker`.
+#[derive(Debug, Clone)]
+enum Executor {
+    Local(RunDir),
+    Remote(RemoteWorker),
+}
+
+impl Executor {
+    fn name(&self) -> String {
+        match self {
+            Self::Local(run_dir) => run_dir.path.display().to_string(),
+            Self::Remote(worker) => worker.name.clone(),
+        }
+    }
+
+    /// Whether it can run `Platform::Sgx`.
+    fn sgx(&self) -> bool {
+        match self {
+            Self::Local(_) => sgx_available(),
+            Self::Remote(worker) => worker.sgx,
+        }
+    }
+}
+
+/// Whether the job has runs on `Platform::Sgx`. Performance reports always compare the SGX throughput.
+fn uses_sgx(conf: &JobConfig) -> bool {
+    match conf {
+        JobConfig::Profiling(c) => c.platforms.contains(&Platform::Sgx),
+        JobConfig::PerfReport(_) => true,
+        JobConfig::Compile(_) => false,
+    }
+}
+
+/// The commands of the performance report of `commit`, one list per chart.
+fn perf_report_commands(commit: &Commit) -> Vec<Vec<Commandline>> {
+    // The baseline is built with the profile of the commit too, so they are compared fairly.
+    hardcoded_perf_report_commands(commit.id, &commit.baseline, &commit.title, &commit.version)
+        .into_iter()
+        .map(|chart| {
+            chart
+                .into_iter()
+                .map(|c| c.with_profile(&commit.profile))
+                .collect()
+        })
+        .collect()
+}
+
+/// The results that the server has cached for the commands of `job`, so that a worker doesn't repeat them. Results without fingerprint are left out, so a worker doesn't use historical results of the server.
+fn cached_runs(job: &Job, commits: &CommitState, conn: &Arc<Mutex<Connection>>) -> Vec<CachedRun> {
+    let charts = match &job.config {
+        JobConfig::Profiling(c) => c.to_teebench_cmd(),
+        JobConfig::PerfReport(c) => commits
+            .get_by_id(&c.id)
+            .map(perf_report_commands)
+            .unwrap_or_default(),
+        JobConfig::Compile(_) => vec![],
+    };
+    let code_hashmap = commits.get_used_code(&job.config.used_algorithms());
+    let mut searched = vec![];
+    let mut runs = vec![];
+    for cmd in charts.iter().flatten() {
+        let args = cmd.to_teebench_args();
+        let Ok(algorithm) = algorithm_key(&cmd.algorithm, &code_hashmap) else {
+            continue;
         };
+        // Charts can share commands.
+        if searched.contains(&(args.clone(), algorithm.clone())) {
+            continue;
+        }
+        match search_cached_runs(conn.clone(), &args, &algorithm) {
+            Ok(found) => runs.extend(found.into_iter().map(|(fingerprint, data)| CachedRun {
+                args: args.clone(),
+                algorithm: algorithm.clone(),
+                fingerprint,
+                data,
+            })),
+            Err(e) => error!("Searching the cache failed with: {e}"),
+        }
+        searched.push((args, algorithm));
+    }
+    runs
+}
+
+/// The operator whose status or performance report the job changes.
+fn changed_commit(conf: &JobConfig) -> Option<CommitIdType> {
+    match conf {
+        JobConfig::Compile(id) => Some(*id),
+        JobConfig::PerfReport(c) => Some(c.id),
+        JobConfig::Profiling(_) => None,
+    }
+}
+
+/// Removes the finished job from the queue and sends it to the server.
+async fn finish_job(finished_job: Job, ctx: &JobContext) {
+    if let Err(e) = save_job(ctx.conn.clone(), &finished_job) {
+        error!(
+            "Failed to persist finished job {:?}: {e:#}",
+            finished_job.id
+        );
+    }
+    {
+        let mut guard = ctx.queue.lock().unwrap();
+        guard.retain(|j| j.id != finished_job.id);
+    }
+    let id = finished_job.id;
+    ctx.queue_tx.send(finished_job).await.unwrap();
+    ctx.done_tx.send(id).await.unwrap();
+}
+
+/// Runs `job` in `run_dir`.
+#[instrument(skip(job, run_dir, ctx), fields(job = ?job.id, run_dir = ?run_dir.path))]
+async fn work_on_job(job: Job, run_dir: RunDir, ctx: JobContext) {
+    info!("Working on {job:#?}...");
+    let now = Instant::now();
+    let result = guarded_runner(
+        job.config.clone(),
+        job.id,
+        &run_dir,
+        ctx.commits.clone(),
+        ctx.conn.clone(),
+        ctx.partial_results_sender.clone(),
+    )
+    .await;
+    let runtime = now.elapsed();
+    let result_type = if result.is_ok() {
+        "Success".to_string()
+    } else {
+        format!("Failure: {result:?}")
+    };
+    info!("Process completed: {result_type}.");
+    let finished_job = Job {
+        status: JobStatus::Done { runtime },
+        result: Some(result),
+        ..job
+    };
+    finish_job(finished_job, &ctx).await;
+}
+
+/// Sends `job` to `worker` and caches the results it streams back. If the worker disconnects, the job waits in the queue again.
+#[instrument(skip(job, worker, ctx), fields(job = ?job.id, worker = worker.name))]
+async fn work_on_remote_job(job: Job, worker: RemoteWorker, ctx: JobContext) {
+    info!("Sending {job:#?} to worker {}...", worker.name);
+    // Like `runner` does for local jobs.
+    if let JobConfig::PerfReport(c) = &job.config {
+        update_commit(&ctx.commits, &ctx.conn, &c.id, |c| {
+            c.perf_report_running = PerfReportStatus::Running(job.id);
+        });
+    }
+    let (commits, cached) = {
+        let guard = ctx.commits.lock().unwrap();
+        let commits = job
+            .config
+            .used_algorithms()
+            .iter()
+            .filter_map(|a| match a {
+                Algorithm::Commit(id) => guard.get_by_id(id).cloned(),
+                _ => None,
+            })
+            .collect();
+        (commits, cached_runs(&job, &guard, &ctx.conn))
+    };
+    let mut rx = worker.rx.lock().await;
+    let mut sent = worker
+        .tx
+        .send(ToWorker::Run {
+            job: job.clone(),
+            commits,
+            cached,
+        })
+        .await
+        .is_ok();
+    while sent {
+        match rx.recv().await {
+            Some(FromWorker::Run(run)) => {
+                if let Err(e) = insert_experiment(
+                    ctx.conn.clone(),
+                    run.args,
+                    &run.algorithm,
+                    Some(&run.fingerprint),
+                    Some(&worker.name),
+                    run.data,
+                    true,
+                ) {
+                    error!("Failed to cache a result of worker {}: {e:#}", worker.name);
+                }
+            }
+            Some(FromWorker::Progress(id, progress)) if id == job.id => {
+                send_progress(&ctx.partial_results_sender, id, progress).await;
+            }
+            Some(FromWorker::Finished {
+                job: finished_job,
+                commit,
+            }) if finished_job.id == job.id => {
+                info!("Worker {} finished the job.", worker.name);
+                // The worker only decides the outcome of the job, not what the job or the operator are.
+                match commit {
+                    Some(commit) if changed_commit(&job.config) == Some(commit.id) => {
+                        update_commit(&ctx.commits, &ctx.conn, &commit.id, |c| {
+                            c.compilation = commit.compilation;
+                            c.report = commit.report;
+                            c.perf_report_running = commit.perf_report_running;
+                        });
+                    }
+                    Some(commit) => warn!(
+                        "Worker {} sent operator {} that the job does not change, ignoring it.",
+                        worker.name, commit.id
+                    ),
+                    None => (),
+                }
+                let finished_job = Job {
+                    status: finished_job.status,
+                    result: finished_job.result,
+                    ..job
+                };
+                finish_job(finished_job, &ctx).await;
+                return;
+            }
+            Some(msg) => debug!("Ignoring message of another job: {msg:?}"),
+            None => sent = false,
+        }
+    }
+    warn!(
+        "Worker {} disconnected, putting the job back into the queue.",
+        worker.name
+    );
+    requeue_job(&job.id, &ctx);
+    ctx.done_tx.send(job.id).await.unwrap();
+}
+
+/// Lets a job that was stopped wait in the queue again.
+fn requeue_job(id: &JobIdType, ctx: &JobContext) {
+    let job = {
+        let mut guard = ctx.queue.lock().unwrap();
+        guard.iter_mut().find(|j| j.id == *id).map(|j| {
+            j.status = JobStatus::Waiting;
+            j.progress = None;
+            j.clone()
+        })
+    };
+    // Only updates the status shown to the client, so don't wait for a full channel.
+    if let Some(job) = job {
+        if ctx.queue_tx.try_send(job).is_err() {
+            debug!("Could not notify the server that job {id:?} waits again.");
+        }
+    }
+}
+
+/// Marks the first job that can start now, and that `can_start` accepts, as running and returns it.
+///
+/// A job waits for earlier jobs that compile one of its operators or write their performance report, so that eg. a performance report starts after the compilation of its operator. Independent jobs may overtake each other.
+fn start_next_job(
+    queue: &Arc<Mutex<VecDeque<Job>>>,
+    can_start: impl Fn(&Job) -> bool,
+) -> Option<Job> {
+    let mut guard = queue.lock().unwrap();
+    let mut blocked = HashSet::new();
+    let mut next = None;
+    for (i, job) in guard.iter().enumerate() {
+        if job.status == JobStatus::Waiting
+            && job.config.used_algorithms().is_disjoint(&blocked)
+            && can_start(job)
         {
-            let mut guard = queue.lock().unwrap();
-            guard.pop_front();
+            next = Some(i);
+            break;
+        }
+        if let Some(id) = changed_commit(&job.config) {
+            blocked.insert(Algorithm::Commit(id));
         }
-        queue_tx.send(finished_job).await.unwrap();
     }
+    let job = &mut guard[next?];
+    job.status = JobStatus::Running;
+    Some(job.clone())
 }
 
-#[instrument(skip(queue, rx))]
-/// Receives the new job and notifies websocket about the new job
-// TODO that's weird... The websocket received the job, so why can't it react to the new job without a notification from here? So that it can select! what to do?
+/// Starts waiting jobs until every executor is busy or no job can start.
 ///
-/// - queue: still the actual queue, to queue new jobs
-/// - rx: channel to webserver, to receive new jobs from the websocket
-async fn receive_confs(
-    queue: Arc<Mutex<VecDeque<Job>>>,
-    rx: Arc<tokio::sync::Mutex<mpsc::Receiver<Job>>>,
+/// running: the jobs that are running right now with the name of their executor
+fn dispatch_jobs(
+    executors: &[Executor],
+    running: &mut HashMap<JobIdType, (String, JoinHandle<()>)>,
+    ctx: &JobContext,
 ) {
-    let mut rx_guard = rx.lock().await;
-    match rx_guard.recv().await {
-        Some(job) => {
-            info!("New job came in!");
-            let mut guard = queue.lock().unwrap();
-            guard.push_back(job);
+    loop {
+        let free: Vec<&Executor> = executors
+            .iter()
+            .filter(|e| !running.values().any(|(name, _)| *name == e.name()))
+            .collect();
+        if free.is_empty() {
+            return;
         }
-        None => warn!("Received None!"), // TODO Why would this happen?
+        // Jobs with SGX runs wait for an executor with SGX. Only if there is none at all, they run without their SGX runs, see `runner`.
+        let sgx_anywhere = executors.iter().any(Executor::sgx);
+        let sgx_free = free.iter().any(|e| e.sgx());
+        let Some(job) = start_next_job(&ctx.queue, |job| {
+            sgx_free || !sgx_anywhere || !uses_sgx(&job.config)
+        }) else {
+            return;
+        };
+        let needs_sgx = sgx_anywhere && uses_sgx(&job.config);
+        let free: Vec<&Executor> = free.into_iter().filter(|e| !needs_sgx || e.sgx()).collect();
+        // Prefer a run directory that has one of the job's operators compiled in already.
+        let algs = job.config.used_algorithms();
+        let executor = free
+            .iter()
+            .find(|e| match e {
+                Executor::Local(run_dir) => run_dir
+                    .switched_in
+                    .try_lock()
+                    .map(|s| s.map(|a| algs.contains(&a)).unwrap_or(false))
+                    .unwrap_or(false),
+                Executor::Remote(_) => false,
+            })
+            .unwrap_or(&free[0]);
+        info!("Starting job {:?} on {}.", job.id, executor.name());
+        // Only updates the status shown to the client, so don't wait for a full channel.
+        if ctx.queue_tx.try_send(job.clone()).is_err() {
+            debug!("Could not notify the server that job {:?} started.", job.id);
+        }
+        let handle = match executor {
+            Executor::Local(run_dir) => {
+                tokio::spawn(work_on_job(job.clone(), run_dir.clone(), ctx.clone()))
+            }
+            Executor::Remote(worker) => {
+                tokio::spawn(work_on_remote_job(job.clone(), worker.clone(), ctx.clone()))
+            }
+        };
+        running.insert(job.id, (executor.name(), handle));
     }
 }
 
+/// Aborts a running job and waits until its child processes are killed, or tells its worker to do so.
+///
+/// Returns `false` if the job is not running.
+async fn abort_job(
+    id: &JobIdType,
+    executors: &[Executor],
+    running: &mut HashMap<JobIdType, (String, JoinHandle<()>)>,
+) -> bool {
+    let Some((name, handle)) = running.remove(id) else {
+        return false;
+    };
+    handle.abort();
+    // Awaiting the aborted task makes sure its future, and with it every `kill_on_drop` child process, was dropped.
+    let _ = handle.await;
+    match executors.iter().find(|e| e.name() == name) {
+        Some(Executor::Local(run_dir)) => {
+            // The job might have been stopped in the middle of compiling, so the content of `bin` is unknown. If the lock is held, the job was still waiting for `build_baseline` and didn't touch `bin`.
+            if let Ok(mut switched_in) = run_dir.switched_in.try_lock() {
+                switched_in.take();
+            }
+        }
+        Some(Executor::Remote(worker)) => {
+            let _ = worker.tx.try_send(ToWorker::Cancel(*id));
+        }
+        None => (),
+    }
+    true
+}
+
 /// Runs and compiles the all the experiments, and sends the results back to the server (which sends the results to the client).
 ///
-/// Wait for new jobs in a loop. When a new job arrives, queue it and start a task to work on the queue. Then start a new loop to receive new jobs or receive the notification that there are no more jobs in the queue. When that notification arrives, break that loop and restart the outer one.
+/// Every run directory (see `run_dirs::run_dirs_from_env`) and every connected `tbw-worker` works on one job at a time. Whenever a job arrives or finishes, or a worker connects, waiting jobs are started on the free executors, unless the queue is paused.
 ///
 /// commits: The list of commits uploaded, to compile and generate perf reports for them.
 /// queue: the actual queue, shared with the server, so it can send the queue to any newly connecting client
 /// queue_tx: this channel notifies the server of any changes in the queue.
 /// rx: incoming new profiling configs
-#[instrument(skip(commits, queue, queue_tx, rx, cancel_rx, partial_results_sender))]
+/// cancel_rx: jobs that were removed from the queue while running
+/// mode_rx: whether the queue is paused
+/// workers_rx: newly registered remote workers
+/// conn: the cache database, also holding the commits
+/// shutdown_rx: stops the task, interrupting the running jobs
+/// baseline_builds: counts the run directories whose `bin` is built at the start, see `build_baseline`
+/// sgx_executors: set to whether any executor has SGX whenever the executors change
+#[instrument(skip(
+    commits,
+    queue,
+    queue_tx,
+    rx,
+    cancel_rx,
+    mode_rx,
+    workers_rx,
+    partial_results_sender,
+    conn,
+    shutdown_rx,
+    baseline_builds,
+    sgx_executors
+))]
 pub async fn profiling_task(
     commits: Arc<Mutex<CommitState>>,
     queue: Arc<Mutex<VecDeque<Job>>>,
     queue_tx: mpsc::Sender<Job>,
-    rx: mpsc::Receiver<Job>,
-    cancel_rx: mpsc::Receiver<CancelNotifierType>,
-    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<PartialReportType>>>,
+    mut rx: mpsc::Receiver<Job>,
+    mut cancel_rx: mpsc::Receiver<CancelNotifierType>,
+    mut mode_rx: watch::Receiver<QueueMode>,
+    mut workers_rx: mpsc::Receiver<RemoteWorker>,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<JobProgressType>>>,
+    conn: Arc<Mutex<Connection>>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    baseline_builds: BaselineBuildsType,
+    sgx_executors: SgxExecutorsType,
 ) {
-    // Using a tokio Mutex here to make it Send. Which is required...
-    let rx = Arc::new(tokio::sync::Mutex::new(rx));
-    let cancel_rx = Arc::new(tokio::sync::Mutex::new(cancel_rx));
-    // TODO Make this just a &mut, Arc should not be needed except if the compiler requires it, but it is never concurrently accessed.
-    let currently_switched_in = Arc::new(tokio::sync::Mutex::new(None));
-    let conn = setup_sqlite().unwrap();
-    // Connection uses RefCell internally, so the Mutex is required.
-    let conn = Arc::new(Mutex::new(conn));
+    let mut executors: Vec<Executor> = match run_dirs_from_env() {
+        Ok(run_dirs) => run_dirs.into_iter().map(Executor::Local).collect(),
+        Err(e) => {
+            warn!("{e:#}. Jobs only run on remote workers.");
+            vec![]
+        }
+    };
+    for e in &executors {
+        if let Executor::Local(dir) = e {
+            info!("Running jobs in {:?} (CPUs: {:?}).", dir.path, dir.cpus);
+            // Jobs in this run directory wait for the lock until `bin` can be used.
+            let switched_in = dir.switched_in.clone().lock_owned().await;
+            let (path, builds) = (dir.path.clone(), baseline_builds.clone());
+            builds.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                match AssertUnwindSafe(build_baseline(&path)).catch_unwind().await {
+                    Ok(Ok(true)) => info!("Built TeeBench in {path:?}."),
+                    Ok(Ok(false)) => debug!("{path:?} is up to date."),
+                    Ok(Err(e)) => error!("Failed to build TeeBench in {path:?}: {e:#}"),
+                    Err(_) => error!("Building TeeBench in {path:?} panicked."),
+                }
+                builds.fetch_sub(1, Ordering::SeqCst);
+                drop(switched_in);
+            });
+        }
+    }
+    let (done_tx, mut done_rx) = mpsc::channel(DONE_CHANNEL_SIZE);
+    let ctx = JobContext {
+        queue: queue.clone(),
+        queue_tx,
+        commits,
+        conn: conn.clone(),
+        partial_results_sender,
+        done_tx,
+    };
+    let mut running = HashMap::new();
     loop {
-        let handle = {
-            let locked = queue.lock().unwrap();
-            if !locked.is_empty() {
-                Some(tokio::spawn(work_on_queue(
-                    queue.clone(),
-                    queue_tx.clone(),
-                    commits.clone(),
-                    currently_switched_in.clone(),
-                    conn.clone(),
-                    partial_results_sender.clone(),
-                )))
-            } else {
-                None
+        // Disconnected workers don't run a job anymore: their job notices the disconnect and finishes.
+        executors.retain(|e| match e {
+            Executor::Local(_) => true,
+            Executor::Remote(worker) => {
+                worker.is_connected() || running.values().any(|(name, _)| *name == worker.name)
             }
-        };
-        if let Some(handle) = handle {
-            // Futures that are references must be pinned or implement Unpin to be await'ed.
-            tokio::pin!(handle);
-            let mut cancel_rx = cancel_rx.lock().await;
-            loop {
-                tokio::select! {
-                    _ = receive_confs(queue.clone(), rx.clone()) => (),
-                    // By making handle a &mut, it will not be cancelled. Source: https://users.rust-lang.org/t/tokio-select-without-cancellation/71300/6
-                    _ = &mut handle => break,
-                    _ = cancel_rx.recv() => {
-                        info!("Cancelled current job!");
-                        handle.abort();
-                        break;
+        });
+        sgx_executors.store(executors.iter().any(Executor::sgx), Ordering::SeqCst);
+        if *mode_rx.borrow() == QueueMode::Running {
+            dispatch_jobs(&executors, &mut running, &ctx);
+        }
+        tokio::select! {
+            Some(job) = rx.recv() => {
+                info!("New job came in!");
+                let mut guard = queue.lock().unwrap();
+                enqueue(&mut guard, job);
+            }
+            Some(id) = done_rx.recv() => {
+                if let Some((_, handle)) = running.remove(&id) {
+                    let _ = handle.await;
+                }
+            }
+            Some(worker) = workers_rx.recv() => {
+                // A worker that registers again lost its connection, maybe without the server noticing yet.
+                if let Some(i) = executors
+                    .iter()
+                    .position(|e| matches!(e, Executor::Remote(w) if w.name == worker.name))
+                {
+                    warn!("Worker {} registered again, replacing its old connection.", worker.name);
+                    let ids: Vec<JobIdType> = running
+                        .iter()
+                        .filter(|(_, (name, _))| *name == worker.name)
+                        .map(|(id, _)| *id)
+                        .collect();
+                    for id in ids {
+                        abort_job(&id, &executors, &mut running).await;
+                        requeue_job(&id, &ctx);
                     }
+                    // Dropping the old `RemoteWorker` closes its connection, see `serve_worker`.
+                    executors.remove(i);
+                }
+                if executors.iter().any(|e| e.name() == worker.name) {
+                    warn!("There is already a run directory called {}, ignoring the worker.", worker.name);
+                } else {
+                    info!("Worker {} is ready for jobs (SGX: {}).", worker.name, worker.sgx);
+                    executors.push(Executor::Remote(worker));
                 }
             }
-        } else {
-            receive_confs(queue.clone(), rx.clone()).await;
-        }
-        let mut handle = tokio::spawn(work_on_queue(
-            queue.clone(),
-            queue_tx.clone(),
-            commits.clone(),
-            currently_switched_in.clone(),
-            conn.clone(),
-            partial_results_sender.clone(),
-        ));
-        loop {
-            // Following the advice in the tokio::oneshot documentation to make the rx &mut.
-            let mut cancel_rx = cancel_rx.lock().await;
-            // .await;
-            tokio::select! {
-                _ = receive_confs(queue.clone(), rx.clone()) => {},
-                _ = &mut handle => break,
-                _ = cancel_rx.recv() => {
-                    info!("Cancelled current job!");
-                    handle.abort();
-                    break;
+            Ok(()) = mode_rx.changed() => {
+                let mode = *mode_rx.borrow();
+                info!("Queue mode: {mode:?}");
+                if mode == QueueMode::Preempted {
+                    let ids: Vec<JobIdType> = running.keys().copied().collect();
+                    for id in ids {
+                        abort_job(&id, &executors, &mut running).await;
+                        requeue_job(&id, &ctx);
+                        info!("Preempted job {id:?}.");
+                    }
                 }
             }
+            Some(cancel) = cancel_rx.recv() => {
+                let ids: Vec<JobIdType> = match cancel {
+                    Some(id) => vec![id],
+                    None => running.keys().copied().collect(),
+                };
+                for id in ids {
+                    if abort_job(&id, &executors, &mut running).await {
+                        info!("Cancelled job {id:?}.");
+                    }
+                }
+            }
+            _ = &mut shutdown_rx => {
+                let ids: Vec<JobIdType> = running.keys().copied().collect();
+                for id in ids {
+                    abort_job(&id, &executors, &mut running).await;
+                    // The job is stored as waiting, so it is resumed after a restart, see `save_job`.
+                    info!("Interrupted job {id:?}.");
+                }
+                return;
+            }
         }
     }
 }
 
-/// Consumes `Commandline` and makes a tokio process out of it.
-fn to_command(cmdline: &Commandline) -> TokioCommand {
-    let mut cmd = TokioCommand::new(cmdline.app.to_app_name());
+/// Consumes `Commandline` and makes a tokio process out of it, pinned to `cpus` with `taskset` if given.
+fn to_command(cmdline: &Commandline, cpus: Option<&str>) -> TokioCommand {
+    let mut cmd = match cpus {
+        Some(cpus) => {
+            let mut cmd = TokioCommand::new("taskset");
+            cmd.args(["--cpu-list", cpus, &cmdline.app.to_app_name()]);
+            cmd
+        }
+        None => TokioCommand::new(cmdline.app.to_app_name()),
+    };
     cmd.args(cmdline.args.clone());
     cmd.kill_on_drop(true);
     cmd
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caching::setup_worker_sqlite;
+
+    /// Output of `./native -a RHO -n 2 --csv` with a run per throughput.
+    fn teebench_csv(throughputs: &[&str]) -> String {
+        let columns: Vec<_> = OUTPUT_COLUMNS.iter().filter(|c| c.required).collect();
+        let header: Vec<_> = columns.iter().map(|c| c.name).collect();
+        let mut csv = header.join(",") + "\n";
+        for throughput in throughputs {
+            let row: Vec<_> = columns
+                .iter()
+                .map(|c| match c.name {
+                    "algorithm" => "RHO",
+                    "threads" => "2",
+                    "throughput" => throughput,
+                    _ => "1",
+                })
+                .collect();
+            csv += &(row.join(",") + "\n");
+        }
+        csv
+    }
+
+    #[test]
+    fn test_import_teebench_output() -> Result<()> {
+        let conn = Arc::new(Mutex::new(setup_worker_sqlite(None)?));
+        let count = |table: &str| -> i64 {
+            conn.lock()
+                .unwrap()
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0))
+                .unwrap()
+        };
+        let mut upload = ResultUpload {
+            command: "./native -a RHO -n 2 --csv".to_string(),
+            csv: teebench_csv(&["1", "2", "3"]),
+            source: "test".to_string(),
+            commit: None,
+        };
+        // Storing the last run fails, so none of them is stored.
+        conn.lock().unwrap().execute(
+            "CREATE TRIGGER fail_last BEFORE INSERT ON output WHEN NEW.throughput = 3 BEGIN SELECT RAISE(ABORT, 'disk full'); END",
+            [],
+        )?;
+        let commits = CommitState::new(vec![]);
+        assert!(import_teebench_output(conn.clone(), &upload, &commits).is_err());
+        assert_eq!(count("teebenchargs"), 0);
+        assert_eq!(count("output"), 0);
+        conn.lock().unwrap().execute("DROP TRIGGER fail_last", [])?;
+        assert_eq!(import_teebench_output(conn.clone(), &upload, &commits)?, 3);
+        assert_eq!(count("output"), 3);
+        // Uploads don't add columns.
+        upload.csv = teebench_csv(&["4"])
+            .replacen('\n', ",newCounter\n", 1)
+            .replace("4\n", "4,5\n");
+        let e = import_teebench_output(conn.clone(), &upload, &commits).unwrap_err();
+        assert!(e.to_string().contains("newCounter"));
+        assert_eq!(count("output"), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remaining_time() {
+        let run_timeout = Some(Duration::from_secs(5));
+        assert_eq!(remaining_time(None, None), None);
+        assert_eq!(remaining_time(run_timeout, None), run_timeout);
+        // A job with time left: the run timeout, unless the job ends earlier.
+        let deadline = Some(tokio::time::Instant::now() + Duration::from_secs(60));
+        assert_eq!(remaining_time(run_timeout, deadline), run_timeout);
+        let left = remaining_time(Some(Duration::from_secs(120)), deadline).unwrap();
+        assert!(left > Duration::from_secs(50) && left <= Duration::from_secs(60));
+        let left = remaining_time(None, deadline).unwrap();
+        assert!(left > Duration::from_secs(50) && left <= Duration::from_secs(60));
+        // A job that is out of time.
+        let deadline = tokio::time::Instant::now().checked_sub(Duration::from_secs(1));
+        assert_eq!(remaining_time(run_timeout, deadline), Some(Duration::ZERO));
+        assert_eq!(remaining_time(None, deadline), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_output_logged() {
+        let mut cmd = TokioCommand::new("sh");
+        cmd.args(["-c", "printf 'line\\npartial'; printf 'error' >&2"]);
+        let (log_tx, mut log_rx) = mpsc::unbounded_channel();
+        let output = output_logged(&mut cmd, "sh", Some(&log_tx)).await.unwrap();
+        assert_eq!(output.stdout, b"line\npartial");
+        assert_eq!(output.stderr, b"error");
+        drop(log_tx);
+        let mut lines = vec![];
+        while let Some(line) = log_rx.recv().await {
+            lines.push(line);
+        }
+        assert_eq!(lines[0], OutputLine::Command("sh".to_string()));
+        let stdout: Vec<_> = lines
+            .iter()
+            .filter(|l| matches!(l, OutputLine::Stdout(_)))
+            .collect();
+        assert_eq!(
+            stdout,
+            [
+                &OutputLine::Stdout("line".to_string()),
+                &OutputLine::Stdout("partial".to_string())
+            ]
+        );
+        assert!(lines.contains(&OutputLine::Stderr("error".to_string())));
+    }
+}
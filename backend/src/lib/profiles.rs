@@ -0,0 +1,65 @@
+use anyhow::{bail, Context, Result};
+use std::env::var;
+use std::path::Path;
+
+use common::data_types::BuildProfile;
+
+use crate::config::BUILD_PROFILES_VAR_NAME;
+
+/// The default profile, followed by the profiles in the json file at `TEEBENCHWEB_BUILD_PROFILES` if it is set.
+pub fn build_profiles_from_env() -> Result<Vec<BuildProfile>> {
+    match var(BUILD_PROFILES_VAR_NAME) {
+        Ok(path) => read_build_profiles(Path::new(&path)),
+        Err(_) => Ok(vec![BuildProfile::default()]),
+    }
+}
+
+fn read_build_profiles(path: &Path) -> Result<Vec<BuildProfile>> {
+    let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read {path:?}"))?;
+    parse_build_profiles(&json).with_context(|| format!("Invalid build profiles in {path:?}"))
+}
+
+/// Parses a json array of profiles, eg. `[{"name": "release", "native_args": [], "sgx_args": ["SGX_DEBUG=0"]}]`.
+fn parse_build_profiles(json: &str) -> Result<Vec<BuildProfile>> {
+    let mut profiles = vec![BuildProfile::default()];
+    for profile in serde_json::from_str::<Vec<BuildProfile>>(json)? {
+        if !profile.has_valid_name() {
+            bail!(
+                "Invalid profile name `{}`, use up to 64 letters, digits, `-` and `_`",
+                profile.name
+            );
+        }
+        if profiles.iter().any(|p| p.name == profile.name) {
+            bail!("The profile `{}` is defined twice", profile.name);
+        }
+        profiles.push(profile);
+    }
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_profiles() -> Result<()> {
+        let profiles = parse_build_profiles(
+            r#"[{"name": "release", "native_args": [], "sgx_args": ["SGX_DEBUG=0", "SGX_MODE=HW"]}]"#,
+        )?;
+        assert_eq!(profiles.len(), 2);
+        assert!(profiles[0].is_default());
+        assert_eq!(
+            profiles[1].sgx_make_args(),
+            ["sgx", "SGX_DEBUG=0", "SGX_MODE=HW"]
+        );
+        assert!(parse_build_profiles(
+            r#"[{"name": "default", "native_args": [], "sgx_args": []}]"#
+        )
+        .is_err());
+        assert!(
+            parse_build_profiles(r#"[{"name": "../bin", "native_args": [], "sgx_args": []}]"#)
+                .is_err()
+        );
+        Ok(())
+    }
+}
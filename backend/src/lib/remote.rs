@@ -0,0 +1,344 @@
+//! Remote workers: `tbw-worker` runs jobs in its own TeeBench directories for a central server.
+//!
+//! A worker connects to `/api/worker` with a websocket and registers with its name and the shared secret. The server sends it one job at a time, together with the operators the job uses. The worker streams every TeeBench run back, so the server caches it (with the worker's name as source), and finally sends the finished job.
+
+use anyhow::{bail, Context, Result};
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{SinkExt, StreamExt};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env::var;
+use std::sync::{Arc, Mutex};
+use time::Instant;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message as ClientMessage;
+use tracing::{debug, error, info, warn};
+
+use common::commit::{Commit, CommitState};
+use common::data_types::{Job, JobIdType, JobStatus, TeebenchArgs};
+
+use crate::caching::{insert_experiment, search_for_exp};
+use crate::config::WORKER_SECRET_VAR_NAME;
+use crate::run_dirs::RunDir;
+use crate::sgx::sgx_available;
+use crate::{changed_commit, guarded_runner, JobProgress, ALL_SAMPLES};
+
+/// A TeeBench run that a worker cached, see `caching::insert_experiment`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedRun {
+    pub args: TeebenchArgs,
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub data: HashMap<String, String>,
+}
+
+/// Messages from the server to a worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ToWorker {
+    /// Run the job. `commits` are the operators it uses, `cached` the results the server has for its runs.
+    Run {
+        job: Job,
+        commits: Vec<Commit>,
+        cached: Vec<CachedRun>,
+    },
+    Cancel(JobIdType),
+}
+
+/// Messages from a worker to the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FromWorker {
+    /// First message after connecting. `secret` must be the server's, see `worker_secret_from_env`. `sgx` is whether the worker has SGX, see `sgx::sgx_available`.
+    Register {
+        name: String,
+        secret: String,
+        sgx: bool,
+    },
+    Run(CachedRun),
+    Progress(JobIdType, JobProgress),
+    /// The job is done. `commit` is the operator it compiled or created the performance report for.
+    Finished {
+        job: Job,
+        commit: Option<Commit>,
+    },
+}
+
+/// The server's end of a connected `tbw-worker`. It runs one job at a time.
+#[derive(Debug, Clone)]
+pub struct RemoteWorker {
+    pub name: String,
+    /// Whether it can run `Platform::Sgx`.
+    pub sgx: bool,
+    pub tx: mpsc::Sender<ToWorker>,
+    /// Only the job that runs on the worker reads its messages.
+    pub rx: Arc<tokio::sync::Mutex<mpsc::Receiver<FromWorker>>>,
+}
+
+impl RemoteWorker {
+    pub fn is_connected(&self) -> bool {
+        !self.tx.is_closed()
+    }
+}
+
+/// The secret that workers register with, from `TEEBENCHWEB_WORKER_SECRET`. Workers run any job the server sends them and their results end up in the server's cache, so without a secret the server accepts no workers.
+pub fn worker_secret_from_env() -> Option<String> {
+    var(WORKER_SECRET_VAR_NAME).ok().filter(|s| !s.is_empty())
+}
+
+/// Relays between the websocket of a worker and its `RemoteWorker`, which is handed to `profiling_task` after the worker registered.
+///
+/// secret: the secret the worker must register with, see `worker_secret_from_env`
+pub async fn serve_worker(
+    mut socket: WebSocket,
+    workers_tx: mpsc::Sender<RemoteWorker>,
+    secret: Option<String>,
+) {
+    let (name, sgx) = match socket.recv().await {
+        Some(Ok(Message::Binary(b))) => match serde_json::from_slice(&b) {
+            Ok(FromWorker::Register {
+                name,
+                secret: worker_secret,
+                sgx,
+            }) => {
+                if secret.as_deref() != Some(worker_secret.as_str()) {
+                    warn!("Worker {name} did not send the right secret, closing the connection.");
+                    return;
+                }
+                (name, sgx)
+            }
+            _ => {
+                warn!("Worker did not register, closing the connection.");
+                return;
+            }
+        },
+        _ => return,
+    };
+    let (to_tx, mut to_rx) = mpsc::channel(4);
+    let (from_tx, from_rx) = mpsc::channel(64);
+    let worker = RemoteWorker {
+        name: name.clone(),
+        sgx,
+        tx: to_tx,
+        rx: Arc::new(tokio::sync::Mutex::new(from_rx)),
+    };
+    if workers_tx.send(worker).await.is_err() {
+        return;
+    }
+    info!("Worker {name} registered.");
+    loop {
+        tokio::select! {
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Binary(b))) => match serde_json::from_slice(&b) {
+                    Ok(msg) => {
+                        if from_tx.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("Invalid message from worker {name}: {e}"),
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => (),
+            },
+            msg = to_rx.recv() => {
+                // The server dropped the worker, eg. because it registered again.
+                let Some(msg) = msg else {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                };
+                let serialized = serde_json::to_vec(&msg).unwrap();
+                if socket.send(Message::Binary(serialized)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    info!("Worker {name} disconnected.");
+}
+
+/// Connects to the server at `url` and runs its jobs in `run_dir` until the connection closes.
+///
+/// secret: the server's secret, see `worker_secret_from_env`
+/// conn: the worker's own cache, so it does not repeat runs of earlier jobs
+pub async fn run_worker(
+    url: &str,
+    name: &str,
+    secret: &str,
+    run_dir: &RunDir,
+    conn: Arc<Mutex<Connection>>,
+) -> Result<()> {
+    let (socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .with_context(|| format!("Failed to connect to {url}"))?;
+    let (mut sink, mut stream) = socket.split();
+    let send = |msg: FromWorker| ClientMessage::Binary(serde_json::to_vec(&msg).unwrap());
+    sink.send(send(FromWorker::Register {
+        name: name.to_string(),
+        secret: secret.to_string(),
+        sgx: sgx_available(),
+    }))
+    .await?;
+    info!(
+        "Registered as {name} at {url}, running jobs in {:?}.",
+        run_dir.path
+    );
+    let (results_tx, mut results_rx) = mpsc::unbounded_channel();
+    let (partial_tx, mut partial_rx) = mpsc::channel(256);
+    let partial_tx = Arc::new(tokio::sync::Mutex::new(partial_tx));
+    let (finished_tx, mut finished_rx) = mpsc::channel(1);
+    let run_dir = RunDir {
+        results_tx: Some(results_tx),
+        ..run_dir.clone()
+    };
+    let mut current: Option<(JobIdType, JoinHandle<()>)> = None;
+    loop {
+        // Biased, so that all runs and the progress of a job are sent before it is finished.
+        let msg = tokio::select! {
+            biased;
+            Some(run) = results_rx.recv() => FromWorker::Run(run),
+            Some((id, progress)) = partial_rx.recv() => FromWorker::Progress(id, progress),
+            Some((job, commit)) = finished_rx.recv() => {
+                current = None;
+                FromWorker::Finished { job, commit }
+            }
+            msg = stream.next() => {
+                let b = match msg {
+                    Some(Ok(ClientMessage::Binary(b))) => b,
+                    Some(Ok(ClientMessage::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => bail!("Connection to {url} failed: {e}"),
+                };
+                match serde_json::from_slice(&b) {
+                    Ok(ToWorker::Run {
+                        job,
+                        commits,
+                        cached,
+                    }) => {
+                        if let Some((id, _)) = &current {
+                            error!("Server sent job {:?} while {id:?} is running.", job.id);
+                            continue;
+                        }
+                        info!("Received job {:?}.", job.id);
+                        match add_server_runs(&conn, cached) {
+                            Ok(n) => debug!("Added {n} run(s) from the cache of the server."),
+                            Err(e) => warn!("Failed to add the runs of the server to the cache: {e:#}"),
+                        }
+                        let id = job.id;
+                        let handle = tokio::spawn(work_on_remote_job(
+                            job,
+                            commits,
+                            run_dir.clone(),
+                            conn.clone(),
+                            partial_tx.clone(),
+                            finished_tx.clone(),
+                        ));
+                        current = Some((id, handle));
+                    }
+                    Ok(ToWorker::Cancel(id)) => {
+                        if matches!(&current, Some((running, _)) if *running == id) {
+                            let (_, handle) = current.take().unwrap();
+                            handle.abort();
+                            let _ = handle.await;
+                            // The job might have been stopped in the middle of compiling, so the content of `bin` is unknown.
+                            run_dir.switched_in.lock().await.take();
+                            info!("Cancelled job {id:?}.");
+                        }
+                    }
+                    Err(e) => warn!("Invalid message from the server: {e}"),
+                }
+                continue;
+            }
+        };
+        sink.send(send(msg)).await?;
+    }
+    if let Some((_, handle)) = current {
+        handle.abort();
+        let _ = handle.await;
+        run_dir.switched_in.lock().await.take();
+    }
+    Ok(())
+}
+
+/// The arguments, algorithm and fingerprint of a `CachedRun`.
+type CachedRunKey = (TeebenchArgs, String, String);
+
+/// Adds the results that the server has cached to the worker's cache, so the job doesn't repeat them. Results that the worker has already, eg. because it measured them itself, are not added again.
+fn add_server_runs(conn: &Arc<Mutex<Connection>>, runs: Vec<CachedRun>) -> Result<usize> {
+    // The results of the worker for each run and build. Each result of the server matches up with at most one of them.
+    let mut known: Vec<(CachedRunKey, Vec<HashMap<String, String>>)> = vec![];
+    let mut added = 0;
+    for run in runs {
+        let key = (
+            run.args.clone(),
+            run.algorithm.clone(),
+            run.fingerprint.clone(),
+        );
+        let i = match known.iter().position(|(k, _)| *k == key) {
+            Some(i) => i,
+            None => {
+                let results = search_for_exp(
+                    conn.clone(),
+                    &run.args,
+                    &run.algorithm,
+                    Some(&run.fingerprint),
+                    ALL_SAMPLES,
+                )?;
+                known.push((key, results));
+                known.len() - 1
+            }
+        };
+        let results = &mut known[i].1;
+        if let Some(j) = results.iter().position(|r| *r == run.data) {
+            results.swap_remove(j);
+            continue;
+        }
+        insert_experiment(
+            conn.clone(),
+            run.args,
+            &run.algorithm,
+            Some(&run.fingerprint),
+            None,
+            run.data,
+            true,
+        )?;
+        added += 1;
+    }
+    Ok(added)
+}
+
+/// Runs a job that the server sent, with a `CommitState` of just the operators it uses.
+async fn work_on_remote_job(
+    job: Job,
+    commits: Vec<Commit>,
+    run_dir: RunDir,
+    conn: Arc<Mutex<Connection>>,
+    partial_results_sender: Arc<tokio::sync::Mutex<mpsc::Sender<crate::JobProgressType>>>,
+    finished_tx: mpsc::Sender<(Job, Option<Commit>)>,
+) {
+    let commits = Arc::new(Mutex::new(CommitState::new(commits)));
+    let now = Instant::now();
+    let result = guarded_runner(
+        job.config.clone(),
+        job.id,
+        &run_dir,
+        commits.clone(),
+        conn,
+        partial_results_sender,
+    )
+    .await;
+    let runtime = now.elapsed();
+    if result.is_ok() {
+        info!("Finished job {:?}.", job.id);
+    } else {
+        warn!("Job {:?} failed: {result:?}", job.id);
+    }
+    let commit =
+        changed_commit(&job.config).and_then(|id| commits.lock().unwrap().get_by_id(&id).cloned());
+    let finished_job = Job {
+        status: JobStatus::Done { runtime },
+        result: Some(result),
+        ..job
+    };
+    debug!("Sending finished job {:?} to the server.", finished_job.id);
+    let _ = finished_tx.send((finished_job, commit)).await;
+}
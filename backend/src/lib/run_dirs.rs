@@ -0,0 +1,92 @@
+use anyhow::{bail, Result};
+use std::env::var;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::config::{RUN_DIRS_VAR_NAME, RUN_DIR_VAR_NAME};
+use crate::remote::CachedRun;
+use crate::SwitchedInType;
+
+/// A TeeBench checkout that jobs run in.
+///
+/// Only one job at a time works in a run directory, because compiling an operator replaces the binaries in its `bin` folder.
+#[derive(Debug, Clone)]
+pub struct RunDir {
+    pub path: PathBuf,
+    /// CPU list in the format of `taskset --cpu-list` (eg. `0-3,8`). TeeBench runs are pinned to these CPUs.
+    pub cpus: Option<String>,
+    /// The operator that is compiled into `bin` right now.
+    pub switched_in: SwitchedInType,
+    /// Receives a copy of every result that is cached. `tbw-worker` streams them to the server.
+    pub results_tx: Option<mpsc::UnboundedSender<CachedRun>>,
+}
+
+impl RunDir {
+    pub fn new(path: PathBuf, cpus: Option<String>) -> Self {
+        Self {
+            path,
+            cpus,
+            switched_in: Arc::new(tokio::sync::Mutex::new(None)),
+            results_tx: None,
+        }
+    }
+}
+
+/// Reads the pool of run directories from `TEEBENCHWEB_RUN_DIRS`, or uses `TEEBENCHWEB_RUN_DIR` as the only one.
+pub fn run_dirs_from_env() -> Result<Vec<RunDir>> {
+    if let Ok(dirs) = var(RUN_DIRS_VAR_NAME) {
+        return parse_run_dirs(&dirs);
+    }
+    match var(RUN_DIR_VAR_NAME) {
+        Ok(dir) => Ok(vec![RunDir::new(PathBuf::from(dir), None)]),
+        Err(_) => bail!("Neither {RUN_DIRS_VAR_NAME} nor {RUN_DIR_VAR_NAME} is set"),
+    }
+}
+
+/// Parses `;` separated run directories, each optionally followed by `@` and the CPUs to pin it to, eg. `/opt/tee-bench-1@0-7;/opt/tee-bench-2@8-15`.
+fn parse_run_dirs(value: &str) -> Result<Vec<RunDir>> {
+    let mut dirs = vec![];
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (path, cpus) = match entry.rsplit_once('@') {
+            Some((path, cpus)) => (path.trim(), Some(cpus.trim())),
+            None => (entry, None),
+        };
+        if let Some(cpus) = cpus {
+            if cpus.is_empty()
+                || !cpus
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == ',' || c == '-')
+            {
+                bail!("Invalid CPU list `{cpus}` for run directory `{path}`");
+            }
+        }
+        let path = PathBuf::from(path);
+        if dirs.iter().any(|d: &RunDir| d.path == path) {
+            bail!("Run directory {path:?} is listed twice");
+        }
+        dirs.push(RunDir::new(path, cpus.map(str::to_string)));
+    }
+    if dirs.is_empty() {
+        bail!("{RUN_DIRS_VAR_NAME} contains no run directory");
+    }
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_dirs() {
+        let dirs = parse_run_dirs("/opt/tb-1@0-3,8; /opt/tb-2 ;").unwrap();
+        assert_eq!(dirs.len(), 2);
+        assert_eq!(dirs[0].path, PathBuf::from("/opt/tb-1"));
+        assert_eq!(dirs[0].cpus.as_deref(), Some("0-3,8"));
+        assert_eq!(dirs[1].path, PathBuf::from("/opt/tb-2"));
+        assert_eq!(dirs[1].cpus, None);
+        assert!(parse_run_dirs("/opt/tb-1@all").is_err());
+        assert!(parse_run_dirs("/opt/tb-1;/opt/tb-1@2").is_err());
+        assert!(parse_run_dirs(" ; ").is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use std::env::var;
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::config::SGX_VAR_NAME;
+
+/// Device files of the SGX driver in the kernel and of the older out-of-tree driver.
+const SGX_DEVICES: [&str; 2] = ["/dev/sgx_enclave", "/dev/isgx"];
+
+lazy_static::lazy_static! {
+    static ref SGX_AVAILABLE: bool = probe_sgx();
+}
+
+/// Whether this machine can run SGX enclaves in hardware mode. Probed once per process, so a `tbw-worker` answers for its own machine.
+///
+/// Without SGX, `Platform::Sgx` runs are left out of jobs, but `Native` and `SgxSim` still run.
+pub fn sgx_available() -> bool {
+    *SGX_AVAILABLE
+}
+
+fn probe_sgx() -> bool {
+    match var(SGX_VAR_NAME).as_deref() {
+        Ok("1") => true,
+        Ok("0") => false,
+        Ok(value) => {
+            warn!("Ignoring {SGX_VAR_NAME}={value}, it must be 1 or 0.");
+            has_sgx_device()
+        }
+        Err(_) => has_sgx_device(),
+    }
+}
+
+fn has_sgx_device() -> bool {
+    SGX_DEVICES.iter().any(|dev| Path::new(dev).exists())
+}
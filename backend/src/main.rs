@@ -1,33 +1,89 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        FromRef, State,
+        FromRef, Query, State,
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use axum_extra::routing::SpaRouter;
+use rusqlite::Connection;
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
-use tokio::sync::mpsc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 use tracing::{debug, error, info, instrument, warn};
 
-use backend_lib::{profiling_task, CancelNotifierType, PartialReportType};
-use common::commit::{Commit, CommitState};
-use common::data_types::{ClientMessage, Job, JobStatus, ServerMessage};
+use backend_lib::caching::{
+    algorithm_key, cache_statistics, delete_waiting_job, delete_waiting_jobs, import_csv_files,
+    invalidate_results, load_commits, load_finished_jobs, load_queue_paused, load_waiting_jobs,
+    query_results, save_commit, save_job, save_queue_order, save_queue_paused, setup_sqlite,
+    ResultFilter,
+};
+use backend_lib::profiles::build_profiles_from_env;
+use backend_lib::remote::{serve_worker, worker_secret_from_env, RemoteWorker};
+use backend_lib::sgx::sgx_available;
+use backend_lib::{
+    import_teebench_output, profiling_task, BaselineBuildsType, CancelNotifierType, JobProgress,
+    JobProgressType, QueueMode, SgxExecutorsType,
+};
+use common::commit::{Commit, CommitIdType, CommitState};
+use common::data_types::{
+    Algorithm, BuildProfile, CacheStatistics, ClientMessage, Job, JobConfig, JobHistoryPage,
+    JobIdType, JobPriority, JobStatus, ResultUpload, ServerMessage, ServerStatus,
+};
+use common::queue::{bump_job, move_job, queue_order, set_priority};
 
 const DEFAULT_TASK_CHANNEL_SIZE: usize = 5;
+/// Progress is dropped when this many messages wait, see `backend_lib::JobProgress`. Cached runs come in bursts.
+const PROGRESS_CHANNEL_SIZE: usize = 256;
+/// A client that falls this many changes of the queue behind loads it again, see `ServerMessage::QueueOutdated`.
+const QUEUE_UPDATES_CHANNEL_SIZE: usize = 64;
+const DEFAULT_JOB_HISTORY_PAGE_SIZE: usize = 50;
+/// Number of runs returned by `/api/results` without `limit`.
+const DEFAULT_RESULTS_LIMIT: usize = 1000;
+
+/// See `ServerMessage::QueueOrder`.
+type QueueOrderType = Vec<(JobIdType, JobPriority)>;
+
+/// Jobs and commits carry whole build profiles, but only the ones the server offers may be built. Their make variables could run anything.
+fn check_profiles<'a>(
+    offered: &[BuildProfile],
+    requested: impl IntoIterator<Item = &'a BuildProfile>,
+) -> Result<(), (StatusCode, String)> {
+    match requested.into_iter().find(|p| !offered.contains(p)) {
+        Some(p) => {
+            warn!("Refused unknown build profile {p:?}.");
+            Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unknown build profile `{}`.", p.name),
+            ))
+        }
+        None => Ok(()),
+    }
+}
 
 #[instrument(skip(app_state, payload))]
-async fn upload_commit(State(app_state): State<AppState>, Json(payload): Json<Commit>) {
-    let mut guard = app_state.commits.lock().unwrap();
+async fn upload_commit(
+    State(app_state): State<AppState>,
+    Json(payload): Json<Commit>,
+) -> Result<(), (StatusCode, String)> {
     let debug_title = payload.title.clone();
     info!("Received commit: {debug_title}");
+    check_profiles(&app_state.profiles, [&payload.profile])?;
+    if let Err(e) = save_commit(app_state.conn.clone(), &payload) {
+        error!("Failed to persist commit {debug_title}: {e:#}");
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, String::new()));
+    }
+    let mut guard = app_state.commits.lock().unwrap();
     guard.push_commit(payload);
+    Ok(())
 }
 
 #[instrument(skip(app_state))]
@@ -37,33 +93,253 @@ async fn get_commits(State(app_state): State<AppState>) -> Json<Value> {
     Json(json!(*guard.0))
 }
 
+/// The build profiles that commits and profiling jobs can use, see `backend_lib::profiles`.
+#[instrument(skip(app_state))]
+async fn get_profiles(State(app_state): State<AppState>) -> Json<Vec<BuildProfile>> {
+    Json(app_state.profiles.to_vec())
+}
+
+/// What this server and its workers can run, so the frontend can disable the rest.
+async fn get_status(State(app_state): State<AppState>) -> Json<ServerStatus> {
+    Json(ServerStatus {
+        sgx: app_state.sgx_executors.load(Ordering::SeqCst),
+    })
+}
+
 #[instrument(skip(app_state, payload))]
 #[axum_macros::debug_handler]
 async fn run_job(
     State(app_state): State<AppState>,
     Json(payload): Json<Job>,
-) -> Result<(), StatusCode> {
+) -> Result<(), (StatusCode, String)> {
     info!("Received: {:?}", payload);
+    if let JobConfig::Profiling(c) = &payload.config {
+        check_profiles(&app_state.profiles, &c.profiles)?;
+    }
+    // Profiling needs the binaries in `bin`, see `backend_lib::build_baseline`.
+    if matches!(payload.config, JobConfig::Profiling(_))
+        && app_state.baseline_builds.load(Ordering::SeqCst) > 0
+    {
+        warn!(
+            "Refused job {:?}: TeeBench is still being built.",
+            payload.id
+        );
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "TeeBench is still being built, try again when the build has finished.".to_string(),
+        ));
+    }
+    if let Err(e) = save_job(app_state.conn.clone(), &payload) {
+        error!("Failed to persist job {:?}: {e:#}", payload.id);
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, String::new()));
+    }
     app_state.worker_task_tx.send(payload).await.unwrap();
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+struct JobHistoryParams {
+    offset: Option<usize>,
+    limit: Option<usize>,
+    /// One of the `JobConfig` variant names, eg. `Profiling`.
+    kind: Option<String>,
+}
+
+#[instrument(skip(app_state))]
+async fn get_jobs(
+    State(app_state): State<AppState>,
+    Query(params): Query<JobHistoryParams>,
+) -> Result<Json<JobHistoryPage>, StatusCode> {
+    load_finished_jobs(
+        app_state.conn.clone(),
+        params.kind.as_deref(),
+        params.offset.unwrap_or(0),
+        params.limit.unwrap_or(DEFAULT_JOB_HISTORY_PAGE_SIZE),
+    )
+    .map(Json)
+    .map_err(|e| {
+        error!("Failed to load the job history: {e:#}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Cached TeeBench results in long format, see `ResultFilter::from_query` for the parameters.
+///
+/// Returns JSON, or CSV with `format=csv`.
+#[instrument(skip(app_state))]
+async fn get_results(
+    State(app_state): State<AppState>,
+    Query(mut params): Query<HashMap<String, String>>,
+) -> Result<Response, (StatusCode, String)> {
+    let format = params.remove("format");
+    let filter = ResultFilter::from_query(&params, DEFAULT_RESULTS_LIMIT)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("{e:#}")))?;
+    let internal_error = |e: anyhow::Error| {
+        error!("Failed to query the results: {e:#}");
+        (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+    };
+    let table = query_results(app_state.conn.clone(), &filter).map_err(internal_error)?;
+    match format.as_deref() {
+        None | Some("json") => Ok(Json(table.to_json()).into_response()),
+        Some("csv") => Ok((
+            [(header::CONTENT_TYPE, "text/csv")],
+            table.to_csv().map_err(internal_error)?,
+        )
+            .into_response()),
+        Some(f) => Err((StatusCode::BAD_REQUEST, format!("Unknown format `{f}`"))),
+    }
+}
+
+/// Deletes the cached results matching the same filters as `get_results`.
+///
+/// Additionally, `commit=<id>` matches the results of an uploaded operator. Answers with the number of deleted runs.
+#[instrument(skip(app_state))]
+async fn delete_results(
+    State(app_state): State<AppState>,
+    Query(mut params): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    if let Some(id) = params.remove("commit") {
+        if params.contains_key("algorithm") {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "`commit` and `algorithm` cannot be combined".to_string(),
+            ));
+        }
+        let id: CommitIdType = id
+            .parse()
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid commit id: {e}")))?;
+        let guard = app_state.commits.lock().unwrap();
+        let Some(commit) = guard.get_by_id(&id) else {
+            return Err((StatusCode::NOT_FOUND, format!("No commit with id {id}")));
+        };
+        let alg = Algorithm::Commit(id);
+        let key = algorithm_key(&alg, &HashMap::from([(alg, commit.code.clone())]))
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+        params.insert("algorithm".to_string(), key);
+    }
+    let filter = ResultFilter::from_query(&params, DEFAULT_RESULTS_LIMIT)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("{e:#}")))?;
+    match invalidate_results(app_state.conn.clone(), &filter) {
+        Ok(deleted) => Ok(Json(json!({ "deleted": deleted }))),
+        Err(e) if filter.matches_all() => Err((StatusCode::BAD_REQUEST, format!("{e:#}"))),
+        Err(e) => {
+            error!("Failed to invalidate results: {e:#}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, String::new()))
+        }
+    }
+}
+
+/// Caches results of TeeBench runs outside of TeeBenchWeb. Answers with the number of imported runs.
+#[instrument(skip(app_state, payload))]
+async fn upload_results(
+    State(app_state): State<AppState>,
+    Json(payload): Json<ResultUpload>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    info!(
+        "Received results of `{}` from {}",
+        payload.command, payload.source
+    );
+    let commits = app_state.commits.lock().unwrap();
+    match import_teebench_output(app_state.conn.clone(), &payload, &commits) {
+        Ok(imported) => Ok(Json(json!({ "imported": imported }))),
+        Err(e) => {
+            warn!("Rejected uploaded results: {e:#}");
+            Err((StatusCode::BAD_REQUEST, format!("{e:#}")))
+        }
+    }
+}
+
+#[instrument(skip(app_state))]
+async fn get_cache_statistics(
+    State(app_state): State<AppState>,
+) -> Result<Json<Vec<CacheStatistics>>, StatusCode> {
+    cache_statistics(app_state.conn.clone())
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to load the cache statistics: {e:#}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
 #[instrument(skip(app_state))]
 async fn get_queue(State(app_state): State<AppState>) -> impl IntoResponse {
     let guard = app_state.queue.lock().unwrap();
     Json(guard.clone())
 }
 
+/// Switches the queue to `mode` and persists whether it is paused.
+fn change_queue_mode(
+    mode_tx: &watch::Sender<QueueMode>,
+    conn: &Arc<Mutex<Connection>>,
+    mode: QueueMode,
+) {
+    if let Err(e) = save_queue_paused(conn.clone(), mode != QueueMode::Running) {
+        error!("Failed to persist the queue mode: {e:#}");
+    }
+    mode_tx.send_if_modified(|current| {
+        let changed = *current != mode;
+        *current = mode;
+        changed
+    });
+}
+
+#[derive(Debug, Deserialize)]
+struct PauseParams {
+    /// Stop the running jobs too, see `ClientMessage::PauseQueue`.
+    #[serde(default)]
+    preempt: bool,
+}
+
+#[instrument(skip(app_state))]
+async fn pause_queue(
+    State(app_state): State<AppState>,
+    Query(params): Query<PauseParams>,
+) -> Json<Value> {
+    let mode = if params.preempt {
+        QueueMode::Preempted
+    } else {
+        QueueMode::Paused
+    };
+    info!("Pausing the queue ({mode:?}).");
+    change_queue_mode(&app_state.queue_mode, &app_state.conn, mode);
+    Json(json!({ "paused": true }))
+}
+
+#[instrument(skip(app_state))]
+async fn resume_queue(State(app_state): State<AppState>) -> Json<Value> {
+    info!("Resuming the queue.");
+    change_queue_mode(&app_state.queue_mode, &app_state.conn, QueueMode::Running);
+    Json(json!({ "paused": false }))
+}
+
 //#[instrument(skip(app_state, ws))]
 async fn ws_handler(State(app_state): State<AppState>, ws: WebSocketUpgrade) -> impl IntoResponse {
     info!("ws_handler running.");
-    ws.on_upgrade(|socket| {
+    ws.on_upgrade(move |socket| {
         handle_socket(
             socket,
             app_state.queue,
-            app_state.unqueued_notifier,
+            app_state.unqueued_notifier.subscribe(),
             app_state.cancel_notifier,
-            app_state.partial_results_receiver,
+            app_state.partial_results_notifier.subscribe(),
+            app_state.queue_mode,
+            app_state.queue_order,
+            app_state.conn,
+        )
+    })
+}
+
+/// Connects a `tbw-worker`.
+async fn worker_ws_handler(
+    State(app_state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    info!("A worker is connecting.");
+    ws.on_upgrade(move |socket| {
+        serve_worker(
+            socket,
+            app_state.workers_tx,
+            app_state.worker_secret.as_deref().cloned(),
         )
     })
 }
@@ -72,14 +348,24 @@ async fn ws_handler(State(app_state): State<AppState>, ws: WebSocketUpgrade) ->
 async fn handle_socket(
     mut socket: WebSocket,
     queue: Arc<Mutex<VecDeque<Job>>>,
-    unqueued_notifier: Arc<tokio::sync::Mutex<mpsc::Receiver<Job>>>,
+    mut unqueued_notifier: broadcast::Receiver<Job>,
     cancel_notifier: Arc<tokio::sync::Mutex<mpsc::Sender<CancelNotifierType>>>,
-    partial_results_receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<PartialReportType>>>,
+    mut partial_results_receiver: broadcast::Receiver<JobProgressType>,
+    queue_mode: Arc<watch::Sender<QueueMode>>,
+    queue_order_tx: Arc<watch::Sender<QueueOrderType>>,
+    conn: Arc<Mutex<Connection>>,
 ) {
+    let mut mode_rx = queue_mode.subscribe();
+    // The client loads the queue itself after connecting, so only later changes are sent.
+    let mut order_rx = queue_order_tx.subscribe();
+    order_rx.borrow_and_update();
+    let paused = *mode_rx.borrow_and_update() != QueueMode::Running;
+    let serialized = serde_json::to_vec(&ServerMessage::QueuePaused(paused)).unwrap();
+    if socket.send(Message::Binary(serialized)).await.is_err() {
+        error!("Sending the queue state to client failed: Client disconnected.");
+        return;
+    }
     loop {
-        let mut unqueued_notifier = unqueued_notifier.lock().await;
-        let cancel_notifier = cancel_notifier.lock().await;
-        let mut partial_results_receiver = partial_results_receiver.lock().await;
         debug!("Looping back to select socket or queue_state channel receiver");
         // TODO Check if data loss could happen due to cancelation.
         tokio::select! {
@@ -101,24 +387,60 @@ async fn handle_socket(
                                             queue.clear();
                                             info!("Queue cleared.");
                                         }
-                                        cancel_notifier.send(true).await.unwrap();
-                                        info!("Cancelled current job.");
+                                        if let Err(e) = delete_waiting_jobs(conn.clone()) {
+                                            error!("Failed to remove the queued jobs from the database: {e:#}");
+                                        }
+                                        cancel_notifier.lock().await.send(None).await.unwrap();
+                                        info!("Cancelled running jobs.");
                                     }
                                     ClientMessage::RemoveJob(id) => {
                                         info!("Removing specific job {id:?}...");
-                                        let mut done = false;
+                                        let mut running = false;
                                         {
                                             let mut queue = queue.lock().unwrap();
                                             if let Some(found) = queue.iter().position(|j| j.id == id) {
+                                                running = queue[found].status == JobStatus::Running;
                                                 queue.remove(found);
-                                                if found != 0 { done = true; }
                                             }
                                         }
-                                        if !done {
-                                            cancel_notifier.send(true).await.unwrap();
+                                        if let Err(e) = delete_waiting_job(conn.clone(), &id) {
+                                            error!("Failed to remove job {id:?} from the database: {e:#}");
+                                        }
+                                        if running {
+                                            cancel_notifier.lock().await.send(Some(id)).await.unwrap();
                                         }
                                         info!("Removed job {id:?}.");
                                     }
+                                    ClientMessage::MoveJob(id, position) => {
+                                        info!("Moving job {id:?} to position {position}...");
+                                        let changed = move_job(&mut queue.lock().unwrap(), &id, position);
+                                        if changed {
+                                            change_queue_order(&queue_order_tx, &queue, &conn);
+                                        }
+                                    }
+                                    ClientMessage::BumpJob(id) => {
+                                        info!("Moving job {id:?} to the front...");
+                                        let changed = bump_job(&mut queue.lock().unwrap(), &id);
+                                        if changed {
+                                            change_queue_order(&queue_order_tx, &queue, &conn);
+                                        }
+                                    }
+                                    ClientMessage::PauseQueue { preempt } => {
+                                        let mode = if preempt { QueueMode::Preempted } else { QueueMode::Paused };
+                                        info!("Pausing the queue ({mode:?}).");
+                                        change_queue_mode(&queue_mode, &conn, mode);
+                                    }
+                                    ClientMessage::ResumeQueue => {
+                                        info!("Resuming the queue.");
+                                        change_queue_mode(&queue_mode, &conn, QueueMode::Running);
+                                    }
+                                    ClientMessage::SetJobPriority(id, priority) => {
+                                        info!("Setting the priority of job {id:?} to {priority}...");
+                                        let changed = set_priority(&mut queue.lock().unwrap(), &id, priority);
+                                        if changed {
+                                            change_queue_order(&queue_order_tx, &queue, &conn);
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -138,33 +460,56 @@ async fn handle_socket(
                     return;
                 }
             },
-            Some(job) = unqueued_notifier.recv() => {
-                info!("Queue receiver got a finished job. Notifying client...");
-                match job.status {
-                    JobStatus::Waiting => {
-                        // TODO Remove getting notified of this here?
+            update = unqueued_notifier.recv() => {
+                let msg = match update {
+                    Ok(job) => match job.status {
+                        JobStatus::Waiting => ServerMessage::JobWaiting(job.id),
+                        JobStatus::Running => ServerMessage::JobStarted(job.id),
+                        JobStatus::Done { .. } => ServerMessage::RemoveQueueItem(job),
                     },
-                    JobStatus::Done { .. } => {
-                        let msg = ServerMessage::RemoveQueueItem(job.clone());
-                        let serialized = serde_json::to_vec(&msg).unwrap();
-                        if socket.send(Message::Binary(serialized)).await.is_err() {
-                            error!("Sending finished queue job to client failed: Client disconnected.");
-                            return;
-                        }
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("A client missed {n} change(s) of the queue, it has to load the queue again.");
+                        ServerMessage::QueueOutdated
                     }
+                    Err(RecvError::Closed) => return,
+                };
+                let serialized = serde_json::to_vec(&msg).unwrap();
+                if socket.send(Message::Binary(serialized)).await.is_err() {
+                    error!("Sending a change of the queue to client failed: Client disconnected.");
+                    return;
                 }
             }
-            Some((job_id, report)) = partial_results_receiver.recv() => {
-                {
-                    let mut queue = queue.lock().unwrap();
-                    if let Some(j) = queue.iter_mut().find(|j| j.id == job_id) {
-                        j.result = Some(common::data_types::JobResult::Exp(Ok(report.clone())));
-                    }
+            Ok(()) = mode_rx.changed() => {
+                let paused = *mode_rx.borrow_and_update() != QueueMode::Running;
+                let serialized = serde_json::to_vec(&ServerMessage::QueuePaused(paused)).unwrap();
+                if socket.send(Message::Binary(serialized)).await.is_err() {
+                    error!("Sending the queue state to client failed: Client disconnected.");
+                    return;
+                }
+            }
+            Ok(()) = order_rx.changed() => {
+                let msg = ServerMessage::QueueOrder(order_rx.borrow_and_update().clone());
+                let serialized = serde_json::to_vec(&msg).unwrap();
+                if socket.send(Message::Binary(serialized)).await.is_err() {
+                    error!("Sending the queue order to client failed: Client disconnected.");
+                    return;
                 }
-                let msg = ServerMessage::PartialReport(job_id, report);
+            }
+            update = partial_results_receiver.recv() => {
+                let msg = match update {
+                    Ok((job_id, JobProgress::Report(report))) => ServerMessage::PartialReport(job_id, report),
+                    Ok((job_id, JobProgress::Run(run))) => ServerMessage::RunProgress(job_id, run),
+                    Ok((_, JobProgress::CompilerOutput(id, lines))) => ServerMessage::CompilerOutput(id, lines),
+                    // The progress is also kept in the queue, so loading it again catches up.
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("A client missed {n} progress update(s), it has to load the queue again.");
+                        ServerMessage::QueueOutdated
+                    }
+                    Err(RecvError::Closed) => return,
+                };
                 let serialized = serde_json::to_vec(&msg).unwrap();
                 if socket.send(Message::Binary(serialized)).await.is_err() {
-                    error!("Sending a partial report to client failed!");
+                    error!("Sending the progress of a job to client failed!");
                     return;
                 }
             }
@@ -172,25 +517,87 @@ async fn handle_socket(
     }
 }
 
+/// Sends every change of the queue from `profiling_task` to all clients.
+async fn relay_queue_updates(mut queue_rx: mpsc::Receiver<Job>, tx: broadcast::Sender<Job>) {
+    while let Some(job) = queue_rx.recv().await {
+        // Fails only while no client is connected.
+        let _ = tx.send(job);
+    }
+}
+
+/// Keeps the latest progress of each job in the queue, so that clients loading the queue see it, and sends the progress to all clients.
+async fn relay_progress(
+    mut progress_rx: mpsc::Receiver<JobProgressType>,
+    queue: Arc<Mutex<VecDeque<Job>>>,
+    tx: broadcast::Sender<JobProgressType>,
+) {
+    while let Some((job_id, progress)) = progress_rx.recv().await {
+        if let Some(job) = queue.lock().unwrap().iter_mut().find(|j| j.id == job_id) {
+            match &progress {
+                JobProgress::Report(report) => {
+                    job.result = Some(common::data_types::JobResult::Exp(Ok(report.clone())));
+                }
+                JobProgress::Run(run) => job.progress = Some(run.clone()),
+                JobProgress::CompilerOutput(..) => {}
+            }
+        }
+        let _ = tx.send((job_id, progress));
+    }
+}
+
+/// Stores the order of the queue after a client changed it, and sends it to every client.
+fn change_queue_order(
+    order_tx: &watch::Sender<QueueOrderType>,
+    queue: &Arc<Mutex<VecDeque<Job>>>,
+    conn: &Arc<Mutex<Connection>>,
+) {
+    let order = queue_order(&queue.lock().unwrap());
+    if let Err(e) = save_queue_order(conn.clone(), &order) {
+        error!("Failed to persist the order of the queue: {e:#}");
+    }
+    order_tx.send_replace(order);
+}
+
 // TODO Would it be better to just wrap the AppState in an Arc? Still would need the Mutexes on the fields.
 #[derive(Debug, Clone, FromRef)]
 struct AppState {
     commits: Arc<Mutex<CommitState>>,
     queue: Arc<Mutex<VecDeque<Job>>>,
-    unqueued_notifier: Arc<tokio::sync::Mutex<mpsc::Receiver<Job>>>,
+    /// Every client subscribes to the changes of the queue, see `relay_queue_updates`.
+    unqueued_notifier: broadcast::Sender<Job>,
     worker_task_tx: Arc<mpsc::Sender<Job>>,
     cancel_notifier: Arc<tokio::sync::Mutex<mpsc::Sender<CancelNotifierType>>>,
-    partial_results_receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<PartialReportType>>>,
+    /// Every client subscribes to the progress of the jobs, see `relay_progress`.
+    partial_results_notifier: broadcast::Sender<JobProgressType>,
+    workers_tx: mpsc::Sender<RemoteWorker>,
+    /// Workers must register with it, see `backend_lib::remote::worker_secret_from_env`.
+    worker_secret: Option<Arc<String>>,
+    /// Every client gets notified when it changes.
+    queue_mode: Arc<watch::Sender<QueueMode>>,
+    /// Every client gets notified when it changes.
+    queue_order: Arc<watch::Sender<QueueOrderType>>,
+    conn: Arc<Mutex<Connection>>,
+    baseline_builds: BaselineBuildsType,
+    sgx_executors: SgxExecutorsType,
+    profiles: Arc<Vec<BuildProfile>>,
 }
 
 impl AppState {
     fn new(
         commits: Arc<Mutex<CommitState>>,
         queue: Arc<Mutex<VecDeque<Job>>>,
-        unqueued_notifier: Arc<tokio::sync::Mutex<mpsc::Receiver<Job>>>,
+        unqueued_notifier: broadcast::Sender<Job>,
         worker_task_tx: Arc<mpsc::Sender<Job>>,
         cancel_notifier: Arc<tokio::sync::Mutex<mpsc::Sender<CancelNotifierType>>>,
-        partial_results_receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<PartialReportType>>>,
+        partial_results_notifier: broadcast::Sender<JobProgressType>,
+        workers_tx: mpsc::Sender<RemoteWorker>,
+        worker_secret: Option<Arc<String>>,
+        queue_mode: Arc<watch::Sender<QueueMode>>,
+        queue_order: Arc<watch::Sender<QueueOrderType>>,
+        conn: Arc<Mutex<Connection>>,
+        baseline_builds: BaselineBuildsType,
+        sgx_executors: SgxExecutorsType,
+        profiles: Arc<Vec<BuildProfile>>,
     ) -> Self {
         AppState {
             commits,
@@ -198,7 +605,15 @@ impl AppState {
             unqueued_notifier,
             worker_task_tx,
             cancel_notifier,
-            partial_results_receiver,
+            partial_results_notifier,
+            workers_tx,
+            worker_secret,
+            queue_mode,
+            queue_order,
+            conn,
+            baseline_builds,
+            sgx_executors,
+            profiles,
         }
     }
 }
@@ -209,40 +624,124 @@ async fn main() {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
+    // Connection uses RefCell internally, so the Mutex is required.
+    let conn = Arc::new(Mutex::new(setup_sqlite().unwrap()));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [command, teebenchargs, output] = args.as_slice() {
+        if command == "import-csv" {
+            match import_csv_files(conn, teebenchargs, output) {
+                Ok((imported, skipped)) => {
+                    info!("Imported {imported} run(s), {skipped} were already cached.")
+                }
+                Err(e) => error!("Import failed: {e:#}"),
+            }
+            return;
+        }
+    }
     let commits: Arc<Mutex<CommitState>>;
     let queue: Arc<Mutex<VecDeque<Job>>>;
     if cfg!(feature = "static") {
         commits = Arc::new(Mutex::new(CommitState::new(vec![])));
         queue = Arc::new(Mutex::new(VecDeque::new()));
     } else {
-        let mut hardcoded_commits = vec![common::hardcoded::predefined_commit()];
-        let (mut append_commits, append_jobs) = common::hardcoded::hardcoded_commits();
-        hardcoded_commits.append(&mut append_commits);
-        commits = Arc::new(Mutex::new(CommitState::new(hardcoded_commits)));
-        queue = Arc::new(Mutex::new(VecDeque::from(append_jobs)));
+        let stored_commits = load_commits(conn.clone()).unwrap();
+        if stored_commits.is_empty() {
+            // First start with this database: Seed it with the hardcoded commits and jobs.
+            let mut hardcoded_commits = vec![common::hardcoded::predefined_commit()];
+            let (mut append_commits, mut append_jobs) = common::hardcoded::hardcoded_commits();
+            hardcoded_commits.append(&mut append_commits);
+            append_jobs.append(&mut common::hardcoded::hardcoded_profiling_jobs());
+            for c in &hardcoded_commits {
+                save_commit(conn.clone(), c).unwrap();
+            }
+            for j in &append_jobs {
+                save_job(conn.clone(), j).unwrap();
+            }
+            commits = Arc::new(Mutex::new(CommitState::new(hardcoded_commits)));
+            queue = Arc::new(Mutex::new(VecDeque::from(append_jobs)));
+        } else {
+            let stored_jobs = load_waiting_jobs(conn.clone()).unwrap();
+            info!(
+                "Loaded {} commit(s) and {} queued job(s) from the database.",
+                stored_commits.len(),
+                stored_jobs.len()
+            );
+            commits = Arc::new(Mutex::new(CommitState::new(stored_commits)));
+            queue = Arc::new(Mutex::new(VecDeque::from(stored_jobs)));
+        }
     }
     let (queue_tx, queue_rx) = mpsc::channel(DEFAULT_TASK_CHANNEL_SIZE);
     let (profiling_tx, profiling_rx) = mpsc::channel(DEFAULT_TASK_CHANNEL_SIZE);
     let (cancel_tx, cancel_rx) = mpsc::channel(DEFAULT_TASK_CHANNEL_SIZE);
-    let (partial_results_sender, partial_results_receiver) =
-        mpsc::channel(DEFAULT_TASK_CHANNEL_SIZE);
+    let (partial_results_sender, partial_results_receiver) = mpsc::channel(PROGRESS_CHANNEL_SIZE);
+    let (workers_tx, workers_rx) = mpsc::channel(DEFAULT_TASK_CHANNEL_SIZE);
+    let (unqueued_notifier, _) = broadcast::channel(QUEUE_UPDATES_CHANNEL_SIZE);
+    let (partial_results_notifier, _) = broadcast::channel(PROGRESS_CHANNEL_SIZE);
+    let mode = if load_queue_paused(conn.clone()).unwrap() {
+        warn!("The queue is paused, no job starts until it is resumed.");
+        QueueMode::Paused
+    } else {
+        QueueMode::Running
+    };
+    let (mode_tx, mode_rx) = watch::channel(mode);
+    let (order_tx, _) = watch::channel(queue_order(&queue.lock().unwrap()));
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let baseline_builds = Arc::new(AtomicUsize::new(0));
+    let sgx_executors = Arc::new(AtomicBool::new(false));
+    let profiles = build_profiles_from_env().expect("Failed to load the build profiles");
+    info!(
+        "Build profiles: {:?}",
+        profiles.iter().map(|p| &p.name).collect::<Vec<_>>()
+    );
+    if sgx_available() {
+        info!("SGX is available.");
+    } else {
+        warn!(
+            "SGX is not available, SGX runs only run on workers with SGX, or are left out of jobs."
+        );
+    }
+    let worker_secret = worker_secret_from_env();
+    if worker_secret.is_none() {
+        info!("TEEBENCHWEB_WORKER_SECRET is not set, remote workers are refused.");
+    }
 
-    tokio::spawn(profiling_task(
+    let profiling_handle = tokio::spawn(profiling_task(
         Arc::clone(&commits),
         Arc::clone(&queue),
         queue_tx,
         profiling_rx,
         cancel_rx,
+        mode_rx,
+        workers_rx,
         Arc::new(tokio::sync::Mutex::new(partial_results_sender)),
+        Arc::clone(&conn),
+        shutdown_rx,
+        Arc::clone(&baseline_builds),
+        Arc::clone(&sgx_executors),
+    ));
+
+    tokio::spawn(relay_queue_updates(queue_rx, unqueued_notifier.clone()));
+    tokio::spawn(relay_progress(
+        partial_results_receiver,
+        Arc::clone(&queue),
+        partial_results_notifier.clone(),
     ));
 
     let app_state = AppState::new(
         commits,
         queue,
-        Arc::new(tokio::sync::Mutex::new(queue_rx)),
+        unqueued_notifier,
         Arc::new(profiling_tx),
         Arc::new(tokio::sync::Mutex::new(cancel_tx)),
-        Arc::new(tokio::sync::Mutex::new(partial_results_receiver)),
+        partial_results_notifier,
+        workers_tx,
+        worker_secret.map(Arc::new),
+        Arc::new(mode_tx),
+        Arc::new(order_tx),
+        conn,
+        baseline_builds,
+        sgx_executors,
+        Arc::new(profiles),
     );
 
     let spa = SpaRouter::new("/assets", "../dist"); // TODO Remove and use the tower middleware instead.
@@ -257,12 +756,103 @@ async fn main() {
         .with_state(app_state.clone())
         .route("/api/ws", get(ws_handler))
         .with_state(app_state.clone())
+        .route("/api/worker", get(worker_ws_handler))
+        .with_state(app_state.clone())
         .route("/api/queue", get(get_queue))
-        .with_state(app_state);
+        .with_state(app_state.clone())
+        .route("/api/queue/pause", post(pause_queue))
+        .with_state(app_state.clone())
+        .route("/api/queue/resume", post(resume_queue))
+        .with_state(app_state.clone())
+        .route("/api/jobs", get(get_jobs))
+        .with_state(app_state.clone())
+        .route(
+            "/api/results",
+            get(get_results).post(upload_results).delete(delete_results),
+        )
+        .with_state(app_state.clone())
+        .route("/api/cache", get(get_cache_statistics))
+        .with_state(app_state.clone())
+        .route("/api/profiles", get(get_profiles))
+        .with_state(app_state.clone())
+        .route("/api/status", get(get_status))
+        .with_state(app_state.clone());
 
     info!("Listening on 0.0.0.0:3000");
     axum::Server::bind(&"0.0.0.0:3000".parse().unwrap())
         .serve(app.into_make_service())
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
+    info!("Stopping the current job...");
+    // If sending fails, the profiling task is already gone.
+    let _ = shutdown_tx.send(());
+    if let Err(e) = profiling_handle.await {
+        error!("Profiling task did not stop cleanly: {e}");
+    }
+    // Only now close the channels to the profiling task, it would otherwise see them closed before the shutdown.
+    drop(app_state);
+    info!("Shutdown complete.");
+}
+
+/// Resolves on Ctrl+C (SIGINT) or SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler!");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler!")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => (),
+        _ = terminate => (),
+    }
+    info!("Received shutdown signal.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::data_types::OutputLine;
+
+    #[tokio::test]
+    async fn test_relay_progress_to_every_client() {
+        let (progress_tx, progress_rx) = mpsc::channel(PROGRESS_CHANNEL_SIZE);
+        let (tx, _) = broadcast::channel(PROGRESS_CHANNEL_SIZE);
+        let mut clients = [tx.subscribe(), tx.subscribe()];
+        let relay = tokio::spawn(relay_progress(
+            progress_rx,
+            Arc::new(Mutex::new(VecDeque::new())),
+            tx,
+        ));
+        let (job_id, commit_id) = (JobIdType::new_v4(), CommitIdType::new_v4());
+        let lines: Vec<_> = (0..10)
+            .map(|i| vec![OutputLine::Stdout(format!("line {i}"))])
+            .collect();
+        for l in &lines {
+            let output = JobProgress::CompilerOutput(commit_id, l.clone());
+            progress_tx.send((job_id, output)).await.unwrap();
+        }
+        drop(progress_tx);
+        relay.await.unwrap();
+        for client in &mut clients {
+            let mut received = vec![];
+            while let Ok((id, progress)) = client.recv().await {
+                assert_eq!(id, job_id);
+                match progress {
+                    JobProgress::CompilerOutput(id, l) if id == commit_id => received.push(l),
+                    p => panic!("Unexpected progress: {p:?}"),
+                }
+            }
+            assert_eq!(received, lines);
+        }
+    }
 }
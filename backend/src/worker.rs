@@ -0,0 +1,75 @@
+//! Runs the jobs of a central TeeBenchWeb server on this machine, see `backend_lib::remote`.
+//!
+//! Uses the TeeBench directories from `TEEBENCHWEB_RUN_DIRS` or `TEEBENCHWEB_RUN_DIR`, each one registers as a worker of its own. The server's secret is read from `TEEBENCHWEB_WORKER_SECRET`.
+use anyhow::{bail, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use structopt::StructOpt;
+use tracing::{info, warn};
+
+use backend_lib::build_baseline;
+use backend_lib::caching::setup_worker_sqlite;
+use backend_lib::remote::{run_worker, worker_secret_from_env};
+use backend_lib::run_dirs::run_dirs_from_env;
+
+/// Time to wait before connecting again after the connection to the server failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "tbw-worker",
+    about = "Runs the jobs of a TeeBenchWeb server in the TeeBench directories of this machine."
+)]
+struct Opt {
+    /// Websocket endpoint of the server.
+    #[structopt(long, default_value = "ws://localhost:3000/api/worker")]
+    server: String,
+    /// Name of this worker, eg. the host name. Its results are cached with this source.
+    #[structopt(long)]
+    name: String,
+    /// File to cache results in, so they are reused by later jobs. Without it, results are only kept in memory.
+    #[structopt(long)]
+    cache: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+    let opt = Opt::from_args();
+    let run_dirs = run_dirs_from_env()?;
+    if opt.name.is_empty() {
+        bail!("The name of the worker must not be empty");
+    }
+    let Some(secret) = worker_secret_from_env() else {
+        bail!("Set TEEBENCHWEB_WORKER_SECRET to the secret of the server");
+    };
+    let conn = Arc::new(Mutex::new(setup_worker_sqlite(opt.cache.as_deref())?));
+    let several = run_dirs.len() > 1;
+    for (i, run_dir) in run_dirs.into_iter().enumerate() {
+        let name = if several {
+            format!("{}-{}", opt.name, i + 1)
+        } else {
+            opt.name.clone()
+        };
+        let (server, secret, conn) = (opt.server.clone(), secret.clone(), conn.clone());
+        tokio::spawn(async move {
+            // Only offer to run jobs once `bin` can be used.
+            if let Err(e) = build_baseline(&run_dir.path).await {
+                warn!("{name}: Failed to build TeeBench: {e:#}");
+            }
+            loop {
+                match run_worker(&server, &name, &secret, &run_dir, conn.clone()).await {
+                    Ok(()) => info!("The server closed the connection of {name}."),
+                    Err(e) => warn!("{name}: {e:#}"),
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+    tokio::signal::ctrl_c().await?;
+    info!("Stopping, running jobs are cancelled.");
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Runs jobs on a `tbw-worker` that is connected to the server, with `fake_teebench` as TeeBench.
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use rusqlite::Connection;
+use std::collections::{HashMap, VecDeque};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio_tungstenite::tungstenite::Message;
+
+use backend_lib::caching::{
+    algorithm_key, insert_experiment, query_results, setup_worker_sqlite, ResultFilter,
+};
+use backend_lib::remote::{run_worker, serve_worker, FromWorker, RemoteWorker, ToWorker};
+use backend_lib::run_dirs::RunDir;
+use backend_lib::{profiling_task, JobProgress, JobProgressType, QueueMode};
+use common::commit::{CommitState, PerfReportStatus};
+use common::data_types::{
+    Algorithm, Dataset, ExperimentType, Job, JobConfig, JobResult, JobStatus, Measurement,
+    Parameter, PerfReportConfig, Platform, ProfilingConfiguration, RunEvent,
+};
+use common::hardcoded::{hardcoded_perf_report_commands, predefined_commit};
+use common::output_schema::OUTPUT_COLUMNS;
+use time::OffsetDateTime;
+
+const SECRET: &str = "secret";
+const WORKER_NAME: &str = "test-worker";
+
+async fn worker_ws_handler(
+    State(workers_tx): State<mpsc::Sender<RemoteWorker>>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(|socket| serve_worker(socket, workers_tx, Some(SECRET.to_string())))
+}
+
+/// A TeeBench directory whose `bin/native` is `fake_teebench`.
+fn fake_run_dir() -> RunDir {
+    let mut dir = std::env::temp_dir();
+    dir.push("TeebenchWeb-remote-worker");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("bin")).expect("Failed to create the run directory!");
+    std::os::unix::fs::symlink(env!("CARGO_BIN_EXE_fake_teebench"), dir.join("bin/native"))
+        .expect("Failed to link fake_teebench!");
+    RunDir::new(dir, None)
+}
+
+/// A server without local run directories, so jobs only run on workers.
+struct Server {
+    url: String,
+    commits: Arc<Mutex<CommitState>>,
+    conn: Arc<Mutex<Connection>>,
+    job_tx: mpsc::Sender<Job>,
+    queue_rx: mpsc::Receiver<Job>,
+    partial_rx: mpsc::Receiver<JobProgressType>,
+    _cancel_tx: mpsc::Sender<Option<common::data_types::JobIdType>>,
+    _mode_tx: watch::Sender<QueueMode>,
+    _shutdown_tx: oneshot::Sender<()>,
+}
+
+fn start_server(commits: CommitState) -> Server {
+    std::env::remove_var("TEEBENCHWEB_RUN_DIR");
+    std::env::remove_var("TEEBENCHWEB_RUN_DIRS");
+    let commits = Arc::new(Mutex::new(commits));
+    let conn = Arc::new(Mutex::new(setup_worker_sqlite(None).unwrap()));
+    let queue = Arc::new(Mutex::new(VecDeque::new()));
+    let (queue_tx, queue_rx) = mpsc::channel(16);
+    let (job_tx, job_rx) = mpsc::channel(1);
+    let (cancel_tx, cancel_rx) = mpsc::channel(1);
+    let (mode_tx, mode_rx) = watch::channel(QueueMode::Running);
+    let (workers_tx, workers_rx) = mpsc::channel(1);
+    let (partial_tx, partial_rx) = mpsc::channel(256);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    tokio::spawn(profiling_task(
+        commits.clone(),
+        queue,
+        queue_tx,
+        job_rx,
+        cancel_rx,
+        mode_rx,
+        workers_rx,
+        Arc::new(tokio::sync::Mutex::new(partial_tx)),
+        conn.clone(),
+        shutdown_rx,
+        Arc::new(AtomicUsize::new(0)),
+        Arc::new(AtomicBool::new(false)),
+    ));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/api/worker", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/api/worker", get(worker_ws_handler))
+        .with_state(workers_tx);
+    tokio::spawn(
+        axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(app.into_make_service()),
+    );
+    Server {
+        url,
+        commits,
+        conn,
+        job_tx,
+        queue_rx,
+        partial_rx,
+        _cancel_tx: cancel_tx,
+        _mode_tx: mode_tx,
+        _shutdown_tx: shutdown_tx,
+    }
+}
+
+impl Server {
+    /// Queues `job` and waits until it is done.
+    async fn run(&mut self, job: &Job) -> Job {
+        self.job_tx.send(job.clone()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(60), async {
+            while let Some(j) = self.queue_rx.recv().await {
+                if j.id == job.id && matches!(j.status, JobStatus::Done { .. }) {
+                    return j;
+                }
+            }
+            panic!("The profiling task stopped.");
+        })
+        .await
+        .expect("The job did not finish in time!")
+    }
+
+    /// Number of runs that `WORKER_NAME` measured.
+    fn worker_runs(&self) -> usize {
+        let filter = ResultFilter::from_query(
+            &HashMap::from([("source".to_string(), WORKER_NAME.to_string())]),
+            100,
+        )
+        .unwrap();
+        query_results(self.conn.clone(), &filter)
+            .unwrap()
+            .rows
+            .len()
+    }
+}
+
+#[tokio::test]
+async fn test_remote_job() {
+    let mut server = start_server(CommitState::new(vec![]));
+    let run_dir = fake_run_dir();
+    let worker_conn = Arc::new(Mutex::new(setup_worker_sqlite(None).unwrap()));
+    let (url, conn) = (server.url.clone(), worker_conn.clone());
+    tokio::spawn(async move {
+        run_worker(&url, WORKER_NAME, SECRET, &run_dir, conn)
+            .await
+            .unwrap();
+    });
+
+    let mut config = ProfilingConfiguration::new(
+        vec![Algorithm::Rho],
+        ExperimentType::Custom,
+        Parameter::Threads,
+        Measurement::Throughput,
+        "2".to_string(),
+        "2".to_string(),
+        "1".to_string(),
+        vec![Dataset::CacheFit],
+        vec![Platform::Native],
+        false,
+    );
+    config.include_historical = true;
+    let job = Job::new(JobConfig::Profiling(config), OffsetDateTime::now_utc());
+    let finished = server.run(&job).await;
+    let Some(JobResult::Exp(Ok(report))) = finished.result else {
+        panic!("The job failed: {:?}", finished.result);
+    };
+    assert_eq!(report.charts.len(), 1);
+    assert!(report.charts[0].results.iter().all(|(_, r)| r.is_ok()));
+    let runs = server.worker_runs();
+    assert!(runs > 0);
+
+    // A worker that lost its own cache gets the results from the server instead of running again.
+    worker_conn
+        .lock()
+        .unwrap()
+        .execute_batch("DELETE FROM output; DELETE FROM teebenchargs;")
+        .unwrap();
+    let again = Job::new(job.config.clone(), OffsetDateTime::now_utc());
+    let finished = server.run(&again).await;
+    assert!(matches!(finished.result, Some(JobResult::Exp(Ok(_)))));
+    assert_eq!(server.worker_runs(), runs);
+    let mut cached = false;
+    while let Ok((id, progress)) = server.partial_rx.try_recv() {
+        if let JobProgress::Run(run) = progress {
+            if id == again.id {
+                cached |= run.event == RunEvent::Cached;
+                assert_ne!(run.event, RunEvent::Started);
+            }
+        }
+    }
+    assert!(cached);
+}
+
+/// A performance report is marked as running when it is sent to a worker, together with the results the server has cached for it.
+#[tokio::test]
+async fn test_remote_perf_report() {
+    let commit = predefined_commit();
+    let server = start_server(CommitState::new(vec![commit.clone()]));
+    let baseline_cmd =
+        hardcoded_perf_report_commands(commit.id, &commit.baseline, &commit.title, &commit.version)
+            .into_iter()
+            .flatten()
+            .find(|c| !c.algorithm.is_commit())
+            .expect("The performance report has no baseline run!")
+            .with_profile(&commit.profile);
+    let args = baseline_cmd.to_teebench_args();
+    let algorithm = algorithm_key(&baseline_cmd.algorithm, &HashMap::new()).unwrap();
+    let data: HashMap<String, String> = OUTPUT_COLUMNS
+        .iter()
+        .filter(|c| c.required)
+        .map(|c| (c.name.to_string(), "1".to_string()))
+        .collect();
+    insert_experiment(
+        server.conn.clone(),
+        args.clone(),
+        &algorithm,
+        Some("build"),
+        None,
+        data,
+        true,
+    )
+    .unwrap();
+
+    // Stands in for `run_worker`, so the test sees what the server sends.
+    let (mut socket, _) = tokio_tungstenite::connect_async(&server.url).await.unwrap();
+    let register = FromWorker::Register {
+        name: WORKER_NAME.to_string(),
+        secret: SECRET.to_string(),
+        sgx: true,
+    };
+    socket
+        .send(Message::Binary(serde_json::to_vec(&register).unwrap()))
+        .await
+        .unwrap();
+    let (config, _) = PerfReportConfig::for_throughput(commit.id, commit.baseline);
+    let job = Job::new(JobConfig::PerfReport(config), OffsetDateTime::now_utc());
+    server.job_tx.send(job.clone()).await.unwrap();
+    let msg = tokio::time::timeout(Duration::from_secs(10), socket.next())
+        .await
+        .expect("The server did not send the job in time!");
+    let Some(Ok(Message::Binary(b))) = msg else {
+        panic!("Unexpected message {msg:?}");
+    };
+    let Ok(ToWorker::Run {
+        job: sent, cached, ..
+    }) = serde_json::from_slice(&b)
+    else {
+        panic!("The server did not send the job!");
+    };
+    assert_eq!(sent.id, job.id);
+    assert_eq!(
+        server
+            .commits
+            .lock()
+            .unwrap()
+            .get_by_id(&commit.id)
+            .unwrap()
+            .perf_report_running,
+        PerfReportStatus::Running(job.id)
+    );
+    assert!(cached
+        .iter()
+        .any(|r| r.args == args && r.algorithm == algorithm && r.fingerprint == "build"));
+}
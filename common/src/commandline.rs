@@ -1,5 +1,7 @@
-use crate::data_types::{Algorithm, Platform, TeebenchArgs};
+use crate::data_types::{Algorithm, BuildProfile, Platform, TeebenchArgs};
+use anyhow::{anyhow, bail};
 use std::fmt::Display;
+use std::path::Path;
 use structopt::StructOpt;
 
 /// Commandline is a builder for a std::process::Command or its tokio equivalent.
@@ -10,6 +12,8 @@ pub struct Commandline {
     pub app: Platform,
     pub algorithm: Algorithm,
     pub args: Vec<String>, // Not a HashSet because order is important and flags could be passed multiple times.
+    /// How the binaries that run this command are built.
+    pub profile: BuildProfile,
 }
 
 impl Commandline {
@@ -18,6 +22,7 @@ impl Commandline {
             app: platform,
             algorithm,
             args: vec![],
+            profile: BuildProfile::default(),
         }
     }
     pub fn with_args(platform: Platform, algorithm: Algorithm, args: &[&str]) -> Self {
@@ -26,6 +31,13 @@ impl Commandline {
             app: platform,
             algorithm,
             args,
+            profile: BuildProfile::default(),
+        }
+    }
+    pub fn with_profile(self, profile: &BuildProfile) -> Self {
+        Self {
+            profile: profile.clone(),
+            ..self
         }
     }
     pub fn add_args<S: Display>(&mut self, name: &str, value: S) {
@@ -52,10 +64,44 @@ impl Commandline {
             }
         }
     }
+    /// Parses a command line as it was typed in a shell, eg. `./sgx -a RHO -n 4 --csv`.
+    ///
+    /// The executable determines the platform (see `Platform::from_app_name`). Quoting is not supported.
+    pub fn parse(line: &str) -> anyhow::Result<Self> {
+        let mut tokens = line.split_whitespace();
+        let Some(app) = tokens.next() else {
+            bail!("The command line is empty");
+        };
+        let app_name = Path::new(app)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(app);
+        let app = Platform::from_app_name(app_name).map_err(|e| anyhow!(e))?;
+        let args: Vec<String> = tokens.map(String::from).collect();
+        let Some(pos) = args.iter().position(|a| a == "-a" || a == "--algorithm") else {
+            bail!("The command line has no algorithm (`-a`)");
+        };
+        let Some(algorithm) = args.get(pos + 1) else {
+            bail!("`-a` has no value");
+        };
+        let algorithm = Algorithm::from_cmd_arg(algorithm).map_err(|e| anyhow!(e))?;
+        let cmd = Self {
+            app,
+            algorithm,
+            args,
+            profile: BuildProfile::default(),
+        };
+        cmd.try_to_teebench_args()?;
+        Ok(cmd)
+    }
+    /// Panics if the arguments are invalid, see `try_to_teebench_args`.
     pub fn to_teebench_args(&self) -> TeebenchArgs {
+        self.try_to_teebench_args().unwrap()
+    }
+    pub fn try_to_teebench_args(&self) -> Result<TeebenchArgs, structopt::clap::Error> {
         let app_name = vec![self.app.to_app_name()];
         let iter = app_name.iter().chain(self.args.iter());
-        let mut args = TeebenchArgs::from_iter_safe(iter).unwrap();
+        let mut args = TeebenchArgs::from_iter_safe(iter)?;
         // TODO Remove app_name as the way to determine the platform, use an environment variable and compile it two times.
         if args.x.is_some() || args.y.is_some() {
             if let Some(x) = args.x {
@@ -74,7 +120,8 @@ impl Commandline {
         }
         args.app_name = self.app;
         args.algorithm = self.algorithm;
-        args
+        args.profile = self.profile.name.clone();
+        Ok(args)
     }
 }
 
@@ -203,4 +250,24 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn parse_commandline() {
+        let cmd =
+            Commandline::parse("/opt/tee-bench/native -a RHO -n 4 -d cache-exceed --csv").unwrap();
+        assert_eq!(
+            cmd,
+            Commandline::with_args(
+                Platform::Native,
+                Algorithm::Rho,
+                &["-a", "RHO", "-n", "4", "-d", "cache-exceed", "--csv"]
+            )
+        );
+        let args = cmd.to_teebench_args();
+        assert_eq!(args.threads, 4);
+        assert_eq!(args.dataset, Dataset::CacheExceed);
+        assert!(Commandline::parse("./sgx -n 4").is_err());
+        assert!(Commandline::parse("./sgx -a RHO -n many").is_err());
+        assert!(Commandline::parse("./other -a RHO").is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::data_types::{Algorithm, JobIdType, JobResult};
+use crate::data_types::{Algorithm, BuildProfile, JobIdType, JobResult};
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString, EnumVariantNames};
 use time::OffsetDateTime;
@@ -60,6 +60,9 @@ pub struct Commit {
     pub perf_report_running: PerfReportStatus,
     /// Which other commit or Algorithm should serve as the baseline. Other commits are identified by Algorithm::Commit(CommitIdType).
     pub baseline: Algorithm,
+    /// How the operator is compiled, for the compilation and its performance report.
+    #[serde(default)]
+    pub profile: BuildProfile,
 }
 
 impl Commit {
@@ -84,6 +87,7 @@ impl Commit {
             compilation: CompilationStatus::Uncompiled,
             perf_report_running: PerfReportStatus::None,
             baseline,
+            profile: BuildProfile::default(),
         }
     }
     pub fn get_title(&self) -> String {
@@ -184,6 +188,7 @@ pub struct UploadCommitFormState {
     pub operator: Option<Operator>,
     pub code: Option<String>,
     pub baseline: Option<Algorithm>,
+    pub profile: BuildProfile,
 }
 
 impl Default for UploadCommitFormState {
@@ -194,6 +199,7 @@ impl Default for UploadCommitFormState {
             operator: Some(Operator::Join),
             code: None,
             baseline: Some(Algorithm::Rho),
+            profile: BuildProfile::default(),
         }
     }
 }
@@ -202,7 +208,7 @@ impl UploadCommitFormState {
     // TODO Can this be converted to some From<Commit> implementation and use the automatic into?
     /// Only call after you verified that the form has been filled in correctly. Otherwise this panics
     pub fn to_commit(&self) -> Commit {
-        let c;
+        let mut c;
         let id = uuid::Uuid::new_v4();
         c = Commit::new(
             self.title.clone().unwrap(),
@@ -214,6 +220,7 @@ impl UploadCommitFormState {
             id,
             self.baseline.clone().unwrap(),
         );
+        c.profile = self.profile.clone();
         c
     }
     pub fn verify(&self) -> bool {
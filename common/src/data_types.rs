@@ -13,6 +13,7 @@ use std::fmt::Display;
 
 use crate::commit::CommitIdType;
 use crate::hardcoded::MAX_THREADS;
+use crate::statistics::Statistics;
 
 // Machine-dependent variables
 pub const CPU_PHYSICAL_CORES: u8 = 8;
@@ -22,6 +23,18 @@ pub const CPU_PHYSICAL_CORES: u8 = 8;
 // pub const L3_SIZE_KB: i32        = 16384;
 pub const EPC_SIZE_KB: u32 = 262144; // 256 MB
 
+/// How often each TeeBench command is run if the config does not say otherwise.
+pub const DEFAULT_REPETITIONS: u32 = 1;
+fn default_repetitions() -> u32 {
+    DEFAULT_REPETITIONS
+}
+
+/// Seconds a single TeeBench run may take if the config does not say otherwise. Runs that take longer are killed.
+pub const DEFAULT_RUN_TIMEOUT: Option<u64> = Some(30 * 60);
+fn default_run_timeout() -> Option<u64> {
+    DEFAULT_RUN_TIMEOUT
+}
+
 // I need my own error type as anyhow::Error does not implement serde's traits.
 // TODO Maybe add a function to quickly convert an anyhow error to mine.
 /// Error type for Experiments.
@@ -35,6 +48,10 @@ pub enum TeeBenchWebError {
     TeeBenchNoOutputData,
     #[error("TeeBench crashed with: {0}")]
     TeeBenchCrash(String),
+    #[error("TeeBench ran out of time and was killed!")]
+    Timeout,
+    #[error("SGX is not available on this machine!")]
+    SgxUnavailable,
     #[error("Unknown error!")]
     #[default]
     Unknown,
@@ -43,6 +60,9 @@ pub enum TeeBenchWebError {
 pub type SingleRunResult = Result<HashMap<String, String>, TeeBenchWebError>;
 pub type ExperimentChartResult = Vec<(TeebenchArgs, SingleRunResult)>;
 
+/// Statistics of the repetitions of a run, by output column. See `crate::statistics::aggregate`.
+pub type ExperimentChartStatistics = Vec<(TeebenchArgs, HashMap<String, Statistics>)>;
+
 pub type UnwrapedSingleRunResult = HashMap<String, String>;
 pub type UnwrapedExperimentResult = Vec<(TeebenchArgs, UnwrapedSingleRunResult)>;
 
@@ -50,8 +70,12 @@ pub type UnwrapedExperimentResult = Vec<(TeebenchArgs, UnwrapedSingleRunResult)>
 // TODO To make ProfilingConfiguration an enum depending on ExperimentType is a bad idea maybe, because then we'd have to match in every dispatch callback modifying the config. So instead we now use the JobConfig enum to accertain which kind of job created this report. That is actually not a problem, because I want to switch the yew form to another struct (that would also be local to the frontend). Only after sending the form off it would become a ProfilingConfiguration.
 pub struct ExperimentChart {
     pub config: JobConfig,
+    /// With repetitions, the numeric values are the means of all samples.
     pub results: ExperimentChartResult,
     pub findings: Vec<Finding>,
+    /// Only contains runs that were repeated.
+    #[serde(default)]
+    pub statistics: ExperimentChartStatistics,
 }
 
 impl ExperimentChart {
@@ -60,6 +84,7 @@ impl ExperimentChart {
             config,
             results,
             findings,
+            statistics: vec![],
         }
     }
     pub fn info(&self) -> String {
@@ -85,14 +110,7 @@ impl ExperimentChart {
         let filtered_and_parsed = self
             .results
             .iter()
-            .filter(|t| {
-                t.0.app_name == platform
-                    && t.0.dataset == dataset
-                    && t.0.algorithm == algorithm
-                    && (threads.is_none() || (threads.is_some() && t.0.threads == threads.unwrap()))
-                    && (x.is_none() || (x.is_some() && t.0.x == x))
-                    && (y.is_none() || (y.is_some() && t.0.y == y))
-            })
+            .filter(|t| Self::args_match(&t.0, platform, dataset, algorithm, threads, x, y))
             .map(|t| {
                 t.1.as_ref()
                     .map_err(|e| anyhow::anyhow!("{e}"))
@@ -108,6 +126,46 @@ impl ExperimentChart {
             .collect::<anyhow::Result<Vec<T>>>();
         filtered_and_parsed
     }
+    /// Like `get_result_values`, but returns the statistics of `field`. Runs without repetitions are skipped.
+    pub fn get_result_statistics(
+        &self,
+        field: &str,
+        platform: Platform,
+        dataset: Dataset,
+        algorithm: Algorithm,
+        threads: Option<u8>,
+        x: Option<u32>,
+        y: Option<u32>,
+    ) -> Vec<&Statistics> {
+        self.statistics
+            .iter()
+            .filter(|t| Self::args_match(&t.0, platform, dataset, algorithm, threads, x, y))
+            .filter_map(|t| t.1.get(field))
+            .collect()
+    }
+    /// Statistics of `field` for the run with exactly these `args`.
+    pub fn statistics_for(&self, args: &TeebenchArgs, field: &str) -> Option<&Statistics> {
+        self.statistics
+            .iter()
+            .find(|t| &t.0 == args)
+            .and_then(|t| t.1.get(field))
+    }
+    fn args_match(
+        args: &TeebenchArgs,
+        platform: Platform,
+        dataset: Dataset,
+        algorithm: Algorithm,
+        threads: Option<u8>,
+        x: Option<u32>,
+        y: Option<u32>,
+    ) -> bool {
+        args.app_name == platform
+            && args.dataset == dataset
+            && args.algorithm == algorithm
+            && (threads.is_none() || (threads.is_some() && args.threads == threads.unwrap()))
+            && (x.is_none() || (x.is_some() && args.x == x))
+            && (y.is_none() || (y.is_some() && args.y == y))
+    }
 
     // pub fn get_results(
     //     &mut self,
@@ -179,7 +237,8 @@ impl JobResult {
 pub enum JobStatus {
     #[default]
     Waiting,
-    // TODO Running ;)
+    /// Started in one of the TeeBench run directories. Several jobs can run at the same time.
+    Running,
     Done {
         runtime: Duration,
     },
@@ -187,6 +246,63 @@ pub enum JobStatus {
 
 pub type JobIdType = uuid::Uuid;
 
+/// Jobs of a higher priority start before all waiting jobs of a lower priority, see `crate::queue`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+)]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// What happened to one TeeBench command line of a running job, see `ServerMessage::RunProgress`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RunEvent {
+    Started,
+    /// All repetitions were found in the cache, nothing ran.
+    Cached,
+    Finished {
+        duration: Duration,
+    },
+    Failed {
+        duration: Duration,
+        error: TeeBenchWebError,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunProgress {
+    /// Number of the command line within the job, starting at 1.
+    pub run: usize,
+    /// Number of command lines of the job.
+    pub total: usize,
+    pub command: String,
+    pub event: RunEvent,
+}
+
+/// A line that a command printed while an operator is compiled, see `ServerMessage::CompilerOutput`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OutputLine {
+    /// The command that prints the following lines.
+    Command(String),
+    Stdout(String),
+    Stderr(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Job {
     pub config: JobConfig,
@@ -194,6 +310,11 @@ pub struct Job {
     pub result: Option<JobResult>,
     pub status: JobStatus,
     pub id: JobIdType,
+    #[serde(default)]
+    pub priority: JobPriority,
+    /// The last `ServerMessage::RunProgress` of the job while it runs. Not stored in the database.
+    #[serde(default)]
+    pub progress: Option<RunProgress>,
 }
 
 impl Default for Job {
@@ -204,6 +325,8 @@ impl Default for Job {
             result: None,
             status: JobStatus::default(),
             id: uuid::Uuid::new_v4(),
+            priority: JobPriority::default(),
+            progress: None,
         }
     }
 }
@@ -218,18 +341,77 @@ impl Job {
     }
 }
 
+/// One page of the finished jobs, newest first, as returned by `GET /api/jobs`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct JobHistoryPage {
+    pub jobs: Vec<Job>,
+    /// Number of finished jobs matching the request, over all pages.
+    pub total: usize,
+}
+
+/// Results of TeeBench runs outside of TeeBenchWeb, as sent to `POST /api/results`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ResultUpload {
+    /// The command line that produced `csv`, eg. `./sgx -a RHO -n 4 --csv`.
+    pub command: String,
+    /// Output of TeeBench with `--csv`, one header and one row per run.
+    pub csv: String,
+    /// Where the results come from, eg. the host name.
+    pub source: String,
+    /// The operator that ran, if `command` uses `-a OperatorJoin`.
+    pub commit: Option<crate::commit::CommitIdType>,
+}
+
+/// Number of cached results of one algorithm on one platform, as returned by `GET /api/cache`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheStatistics {
+    pub platform: String,
+    /// As stored in the cache, ie. `Operator(<hash of the code>)` for commits.
+    pub algorithm: String,
+    pub runs: usize,
+    /// Number of distinct TeeBench builds, results without fingerprint are not counted.
+    pub builds: usize,
+    /// `None` if all runs came from the csv files.
+    pub oldest: Option<OffsetDateTime>,
+    pub newest: Option<OffsetDateTime>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ClientMessage {
     RemoveAllJobs,
     RemoveJob(JobIdType),
+    /// Moves the job to this index of the queue, within its priority class. See `crate::queue::move_job`.
+    MoveJob(JobIdType, usize),
+    /// Makes the job the next one to start.
+    BumpJob(JobIdType),
+    SetJobPriority(JobIdType, JobPriority),
+    /// Stops starting new jobs. Running jobs finish, unless `preempt` is set: then they are stopped and wait in the queue again.
+    PauseQueue {
+        preempt: bool,
+    },
+    ResumeQueue,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ServerMessage {
-    /// Backend has finished the current top queue item and wants the frontend to remove it from the queue.
+    /// Backend has finished a queue item and wants the frontend to remove it from the queue.
     /// Also the frontend should add the attached JobResult to that Job.
     RemoveQueueItem(Job),
+    /// The job with this id left the waiting queue and is running now.
+    JobStarted(JobIdType),
     PartialReport(JobIdType, Report),
+    /// A TeeBench command line of the job started, was found in the cache, finished or failed.
+    RunProgress(JobIdType, RunProgress),
+    /// New lines of the build of this operator, stdout and stderr in the order they were printed.
+    CompilerOutput(CommitIdType, Vec<OutputLine>),
+    /// The queue was reordered. Holds the ids of all jobs in the queue in their new order, with their priority.
+    QueueOrder(Vec<(JobIdType, JobPriority)>),
+    /// The job with this id was stopped and waits in the queue again.
+    JobWaiting(JobIdType),
+    /// Whether the queue is paused, see `ClientMessage::PauseQueue`. Sent to every client when it connects and whenever it changes.
+    QueuePaused(bool),
+    /// The client missed changes of the queue because it didn't keep up with them. It loads the queue from `/api/queue` again, and the commits and finished jobs that the missed changes may have updated.
+    QueueOutdated,
 }
 
 pub fn describe_ui_element(element: &str) -> &str {
@@ -322,9 +504,16 @@ pub fn describe_ui_element(element: &str) -> &str {
         "Platforms" => indoc::indoc!(
             r#"
                 The platforms to run the experiment on.
+                SGX Simulation runs the enclave code with `SGX_MODE=SIM`, which works without SGX hardware. Its results are not measurements of a real enclave.
+                SGX is disabled if the server has no SGX device.
             "#
         ),
         "Pre-Sort Data" => "Sort the dataset so the algorithm/operator doesn't have to do it.",
+        "Repetitions" => "How often each TeeBench command is run. With more than one repetition, the charts show the mean and its 95% confidence interval as error bars.",
+        "Run Timeout" => "Seconds each TeeBench run may take. A run that takes longer is killed and shows up as timed out, the job continues with the next one. Leave empty for no limit.",
+        "Job Timeout" => "Seconds all TeeBench runs of this job may take together. When they are used up, the remaining runs are not started and show up as timed out. Cached results are still used. Leave empty for no limit.",
+        "Build Profiles" => "Run every command once with each of these TeeBench builds, eg. with release enclaves or another compiler. The server administrator defines the profiles. Each profile has its own binaries, so the first run with a profile builds TeeBench first.",
+        "Include Historical Data" => "Also use cached results that were measured with a different TeeBench build (eg. an older revision or other compile flags). Without this, only results from the current build are used and everything else is run again.",
         _ => unimplemented!(),
     }
 }
@@ -489,6 +678,35 @@ pub enum Measurement {
     TotalSystemCpuTime,
 }
 
+impl Measurement {
+    /// The column of the TeeBench output (see `output_schema::OUTPUT_COLUMNS`) holding this measurement.
+    ///
+    /// Returns `None` for measurements that combine multiple columns.
+    pub fn column(&self) -> Option<&'static str> {
+        match self {
+            Self::Throughput => Some("throughput"),
+            Self::TotalEpcPaging => Some("totalEWB"),
+            Self::Phase1Cycles => Some("phase1Cycles"),
+            Self::Phase2Cycles => Some("phase2Cycles"),
+            Self::TotalCycles => Some("cyclesPerTuple"),
+            Self::TotalL2HitRatio => Some("totalL2HitRatio"),
+            Self::TotalL3HitRatio => Some("totalL3HitRatio"),
+            Self::TotalL2CacheMisses => Some("totalL2CacheMisses"),
+            Self::TotalL3CacheMisses => Some("totalL3CacheMisses"),
+            Self::IPC => Some("totalIPC"),
+            Self::IR => Some("totalIR"),
+            Self::TotalVoluntaryCS => Some("totalVoluntaryCS"),
+            Self::TotalInvoluntaryCS => Some("totalInvoluntaryCS"),
+            Self::TotalUserCpuTime => Some("totalUserCpuTime"),
+            Self::TotalSystemCpuTime => Some("totalSystemCpuTime"),
+            Self::TwoPhasesCycles
+            | Self::ThroughputAndTotalEPCPaging
+            | Self::ThroughputAndContextSwitches
+            | Self::ContextSwitches => None,
+        }
+    }
+}
+
 #[derive(
     Debug,
     Copy,
@@ -579,6 +797,9 @@ pub enum Platform {
     #[strum(to_string = "SGX")]
     Sgx,
     Native,
+    /// The SGX build with `SGX_MODE=SIM`. Runs the enclave code without SGX hardware, so its numbers say nothing about real enclaves.
+    #[strum(to_string = "SGX Simulation")]
+    SgxSim,
 }
 
 impl Platform {
@@ -586,6 +807,7 @@ impl Platform {
         match s {
             "fake_teebench" | "sgx" | "app" => Ok(Self::Sgx),
             "native" => Ok(Self::Native),
+            "sgx-sim" => Ok(Self::SgxSim),
             name => {
                 let msg = format!("Platform cannot be {name}");
                 Err(msg)
@@ -596,6 +818,14 @@ impl Platform {
         match self {
             Self::Sgx => "./sgx".to_string(),
             Self::Native => "./native".to_string(),
+            Self::SgxSim => "./sgx-sim".to_string(),
+        }
+    }
+    /// Folder in `bin` that the platform's binary runs in. The simulation build has its own, because its app loads `enclave.signed.so` from the working directory, like the hardware build.
+    pub fn bin_subdir(&self) -> Option<&'static str> {
+        match self {
+            Self::Sgx | Self::Native => None,
+            Self::SgxSim => Some("sim"),
         }
     }
     pub fn arg0_to_platform() -> Platform {
@@ -612,6 +842,113 @@ impl Platform {
     }
 }
 
+/// What the server can run, see `GET /api/status`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Store)]
+pub struct ServerStatus {
+    /// Whether the server or one of its workers has an SGX device (`/dev/sgx_enclave` or `/dev/isgx`). Without one, only `Native` and `SgxSim` run.
+    pub sgx: bool,
+}
+
+impl Default for ServerStatus {
+    /// Until the server answers, nothing is disabled.
+    fn default() -> Self {
+        Self { sgx: true }
+    }
+}
+
+impl ServerStatus {
+    pub fn can_run(&self, platform: Platform) -> bool {
+        self.sgx || platform != Platform::Sgx
+    }
+}
+
+/// Name of the build profile that TeeBench is built with unless another one is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+fn default_profile_name() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+fn default_profiles() -> HashSet<BuildProfile> {
+    HashSet::from([BuildProfile::default()])
+}
+
+/// A named way to build TeeBench, eg. with release enclaves, `-O3` or clang.
+///
+/// Every profile has its own binaries, and its results are cached and charted separately (see `TeebenchArgs::profile`). Jobs and commits carry the whole profile, so they run the same way on every executor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BuildProfile {
+    /// Also names the folder of its binaries, see `has_valid_name`.
+    pub name: String,
+    /// Make variables of `make native`, eg. `CXX=clang++` or `CFLAGS=-DNATIVE_COMPILATION -O3`.
+    pub native_args: Vec<String>,
+    /// Make variables of `make sgx`, eg. `SGX_DEBUG=0`.
+    pub sgx_args: Vec<String>,
+}
+
+impl Default for BuildProfile {
+    /// Debug enclaves with the PCM and SGX counters, how TeeBenchWeb always built TeeBench.
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            native_args: vec!["CFLAGS=-DNATIVE_COMPILATION".to_string()],
+            sgx_args: [
+                "SGX_DEBUG=1",
+                "SGX_PRERELEASE=0",
+                "SGX_MODE=HW",
+                "CFLAGS=-DPCM_COUNT -DSGX_COUNTERS",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl BuildProfile {
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+    /// Letters, digits, `-` and `_`, so the name can be used as a folder name.
+    pub fn has_valid_name(&self) -> bool {
+        !self.name.is_empty()
+            && self.name.len() <= 64
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+    /// Arguments of `make` for the native build, starting with the target.
+    pub fn native_make_args(&self) -> Vec<String> {
+        std::iter::once("native".to_string())
+            .chain(self.native_args.iter().cloned())
+            .collect()
+    }
+    /// Arguments of `make` for the SGX build, starting with the target.
+    pub fn sgx_make_args(&self) -> Vec<String> {
+        std::iter::once("sgx".to_string())
+            .chain(self.sgx_args.iter().cloned())
+            .collect()
+    }
+    /// Arguments of `make` for the SGX build in simulation mode: the SGX build with `SGX_MODE=SIM` instead of the profile's mode.
+    pub fn sgx_sim_make_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = self
+            .sgx_make_args()
+            .into_iter()
+            .filter(|a| !a.starts_with("SGX_MODE="))
+            .collect();
+        args.push("SGX_MODE=SIM".to_string());
+        args
+    }
+}
+
+/// The build profiles the server offers, see `GET /api/profiles`.
+#[derive(Debug, Clone, PartialEq, Store)]
+pub struct BuildProfileState(pub Vec<BuildProfile>);
+
+impl Default for BuildProfileState {
+    fn default() -> Self {
+        Self(vec![BuildProfile::default()])
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PerfReportConfig {
     pub id: CommitIdType,
@@ -620,6 +957,17 @@ pub struct PerfReportConfig {
     pub dataset: Dataset,
     /// As a hack, I'm putting the `id` in here too if `exp_type` EpcPaging to indicate which of the the two Algorithms should be displayed for the Chart.
     pub baseline: Algorithm,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: u32,
+    /// See `ProfilingConfiguration::run_timeout`.
+    #[serde(default = "default_run_timeout")]
+    pub run_timeout: Option<u64>,
+    /// See `ProfilingConfiguration::job_timeout`. Applies to the whole performance report.
+    #[serde(default)]
+    pub job_timeout: Option<u64>,
+    /// Set if the report was made without SGX: it only has native results, and the charts that need SGX are left out.
+    #[serde(default)]
+    pub native_only: bool,
 }
 
 impl PerfReportConfig {
@@ -630,12 +978,20 @@ impl PerfReportConfig {
                 exp_type: ExperimentType::Throughput,
                 dataset: Dataset::CacheFit,
                 baseline,
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
             Self {
                 id,
                 exp_type: ExperimentType::Throughput,
                 dataset: Dataset::CacheExceed,
                 baseline,
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
         )
     }
@@ -646,12 +1002,20 @@ impl PerfReportConfig {
                 exp_type: ExperimentType::Scalability,
                 dataset: Dataset::CacheFit,
                 baseline,
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
             Self {
                 id,
                 exp_type: ExperimentType::Scalability,
                 dataset: Dataset::CacheExceed,
                 baseline,
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
         )
     }
@@ -665,12 +1029,20 @@ impl PerfReportConfig {
                 exp_type: ExperimentType::EpcPaging,
                 dataset: Dataset::CustomSize { x: 0, y: 0 },
                 baseline: Algorithm::Commit(id),
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
             PerfReportConfig {
                 id,
                 exp_type: ExperimentType::EpcPaging,
                 dataset: Dataset::CustomSize { x: 0, y: 0 },
                 baseline: baseline,
+                repetitions: DEFAULT_REPETITIONS,
+                run_timeout: DEFAULT_RUN_TIMEOUT,
+                job_timeout: None,
+                native_only: false,
             },
         )
     }
@@ -725,6 +1097,51 @@ impl Display for JobConfig {
 
 use crate::commit::CommitState;
 impl JobConfig {
+    /// Name of the variant, used to filter the job history.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Profiling(_) => "Profiling",
+            Self::PerfReport(_) => "PerfReport",
+            Self::Compile(_) => "Compile",
+        }
+    }
+    /// How often each TeeBench command of this job is run.
+    pub fn repetitions(&self) -> u32 {
+        match self {
+            Self::Profiling(c) => c.repetitions,
+            Self::PerfReport(c) => c.repetitions,
+            Self::Compile(_) => 1,
+        }
+    }
+    /// Seconds each TeeBench run and all runs of this job may take, see `ProfilingConfiguration::run_timeout` and `ProfilingConfiguration::job_timeout`.
+    pub fn timeouts(&self) -> (Option<u64>, Option<u64>) {
+        match self {
+            Self::Profiling(c) => (c.run_timeout, c.job_timeout),
+            Self::PerfReport(c) => (c.run_timeout, c.job_timeout),
+            Self::Compile(_) => (None, None),
+        }
+    }
+    /// Whether cached results from other TeeBench builds may be used. Performance reports always compare against the current build.
+    pub fn include_historical(&self) -> bool {
+        match self {
+            Self::Profiling(c) => c.include_historical,
+            Self::PerfReport(_) | Self::Compile(_) => false,
+        }
+    }
+    /// The algorithms and operators whose code is needed to run this job.
+    pub fn used_algorithms(&self) -> HashSet<Algorithm> {
+        match self {
+            Self::Profiling(c) => c.algorithms.clone(),
+            Self::PerfReport(c) => {
+                if let Algorithm::Commit(_) = c.baseline {
+                    HashSet::from([c.baseline, Algorithm::Commit(c.id)])
+                } else {
+                    HashSet::from([Algorithm::Commit(c.id)])
+                }
+            }
+            Self::Compile(id) => HashSet::from([Algorithm::Commit(*id)]),
+        }
+    }
     pub fn algorithms(&self, commits: Option<&CommitState>) -> Vec<String> {
         match self {
             Self::Profiling(c) => c.algorithms.iter().map(|a| a.to_string()).collect(),
@@ -780,6 +1197,20 @@ pub struct ProfilingConfiguration {
     pub datasets: HashSet<Dataset>,
     pub platforms: HashSet<Platform>,
     pub sort_data: bool,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: u32,
+    /// Seconds each TeeBench run may take before it is killed. `None` means no limit.
+    #[serde(default = "default_run_timeout")]
+    pub run_timeout: Option<u64>,
+    /// Seconds all TeeBench runs of the job may take together. Runs that are left when it is used up are not started. `None` means no limit.
+    #[serde(default)]
+    pub job_timeout: Option<u64>,
+    /// Use cached results from other TeeBench builds too, see `describe_ui_element("Include Historical Data")`.
+    #[serde(default)]
+    pub include_historical: bool,
+    /// Every command runs once with each of these builds.
+    #[serde(default = "default_profiles")]
+    pub profiles: HashSet<BuildProfile>,
 }
 
 impl ProfilingConfiguration {
@@ -818,6 +1249,11 @@ impl ProfilingConfiguration {
             datasets: dataset,
             platforms: platform,
             sort_data,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: false,
+            profiles: default_profiles(),
         }
     }
 }
@@ -839,6 +1275,11 @@ impl Default for ProfilingConfiguration {
             datasets: HashSet::from([Dataset::CacheFit]),
             platforms: HashSet::from([Platform::default()]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: false,
+            profiles: default_profiles(),
         }
     }
 }
@@ -859,6 +1300,11 @@ impl std::fmt::Display for ProfilingConfiguration {
                 Dataset: {:?}
                 Platform: {:?}
                 Pre-sort data: {}
+                Repetitions: {}
+                Run timeout: {:?}
+                Job timeout: {:?}
+                Include historical data: {}
+                Build profile(s): {:?}
         ",
             self.algorithms,
             self.experiment_type,
@@ -869,7 +1315,12 @@ impl std::fmt::Display for ProfilingConfiguration {
             self.measurement,
             self.datasets,
             self.platforms,
-            self.sort_data
+            self.sort_data,
+            self.repetitions,
+            self.run_timeout,
+            self.job_timeout,
+            self.include_historical,
+            self.profiles.iter().map(|p| &p.name).collect::<Vec<_>>()
         )
     }
 }
@@ -921,8 +1372,24 @@ impl ProfilingConfiguration {
     /// Converts the configuration into a vector of commands for each chart.
     ///
     /// - If multiple Datasets were selected, each dataset goes to a separate chart.
-    /// -
+    /// - Each command is repeated for every build profile, in the same chart.
     pub fn to_teebench_cmd(&self) -> Vec<Vec<Commandline>> {
+        let charts = self.to_teebench_cmd_of_default_profile();
+        let mut profiles: Vec<&BuildProfile> = self.profiles.iter().collect();
+        if profiles.iter().all(|p| p.is_default()) {
+            return charts;
+        }
+        profiles.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        charts
+            .into_iter()
+            .map(|cmds| {
+                cmds.iter()
+                    .flat_map(|cmd| profiles.iter().map(|p| cmd.clone().with_profile(p)))
+                    .collect()
+            })
+            .collect()
+    }
+    fn to_teebench_cmd_of_default_profile(&self) -> Vec<Vec<Commandline>> {
         let custom_ds_flag;
         match self.experiment_type {
             ExperimentType::EpcPaging => panic!(),
@@ -1160,6 +1627,10 @@ pub struct TeebenchArgs {
     ///Change output to only print out data in csv format
     #[structopt(long)]
     pub csv: bool,
+    /// Name of the `BuildProfile` that TeeBench was built with. Not an argument of TeeBench.
+    #[structopt(skip = default_profile_name())]
+    #[serde(default = "default_profile_name")]
+    pub profile: String,
 }
 
 impl Default for TeebenchArgs {
@@ -1182,6 +1653,7 @@ impl Default for TeebenchArgs {
             sort_r: false,
             sort_s: false,
             csv: true,
+            profile: default_profile_name(),
         }
     }
 }
@@ -1212,6 +1684,12 @@ impl TeebenchArgs {
             ..Self::default()
         }
     }
+    pub fn with_profile(self, profile: &str) -> Self {
+        Self {
+            profile: profile.to_string(),
+            ..self
+        }
+    }
     pub fn crkj_mway_wrong_thread_count(&self) -> bool {
         match self.algorithm {
             Algorithm::Crkj | Algorithm::Mway => {
@@ -1255,4 +1733,15 @@ mod tests {
         assert_eq!(ds, "Cache Exceed");
         assert_eq!(Dataset::from_str(&ds).unwrap(), ds_enum);
     }
+    #[test]
+    fn sgx_sim_overrides_sgx_mode() {
+        let args = BuildProfile::default().sgx_sim_make_args();
+        assert_eq!(
+            args.iter().filter(|a| a.starts_with("SGX_MODE=")).count(),
+            1
+        );
+        assert_eq!(args.last().unwrap(), "SGX_MODE=SIM");
+        let pl = Platform::SgxSim.to_string();
+        assert_eq!(Platform::from_str(&pl).unwrap(), Platform::SgxSim);
+    }
 }
@@ -17,6 +17,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
                 include_str!("../code_files/OperatorJoin_HashJoinVersion1.cpp").to_string(),
             ),
             baseline: Option::from(Algorithm::Rho),
+            profile: BuildProfile::default(),
         },
         UploadCommitFormState {
             title: Option::from("HashJoin".to_string()),
@@ -26,6 +27,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
                 include_str!("../code_files/OperatorJoin_HashJoinVersion2.cpp").to_string(),
             ),
             baseline: Option::from(Algorithm::Rho),
+            profile: BuildProfile::default(),
         },
         UploadCommitFormState {
             title: Option::from("HashJoin".to_string()),
@@ -35,6 +37,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
                 include_str!("../code_files/OperatorJoin_HashJoinVersion3.cpp").to_string(),
             ),
             baseline: Option::from(Algorithm::HjV2),
+            profile: BuildProfile::default(),
         },
         UploadCommitFormState {
             title: Option::from("HashJoin".to_string()),
@@ -44,6 +47,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
                 include_str!("../code_files/OperatorJoin_HashJoinVersion4.cpp").to_string(),
             ),
             baseline: Option::from(Algorithm::HjV3),
+            profile: BuildProfile::default(),
         },
         UploadCommitFormState {
             title: Option::from("HashJoin".to_string()),
@@ -53,6 +57,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
                 include_str!("../code_files/OperatorJoin_HashJoinVersion5.cpp").to_string(),
             ),
             baseline: Option::from(Algorithm::HjV2),
+            profile: BuildProfile::default(),
         },
     ]);
 
@@ -122,6 +127,7 @@ pub fn hardcoded_commits() -> (Vec<Commit>, Vec<Job>) {
     (commits, jobs)
 }
 
+/// These show the results shipped in `cached/`, which have no build fingerprint, so they include historical data.
 pub fn hardcoded_profiling_jobs() -> Vec<Job> {
     let configs: Vec<ProfilingConfiguration> = Vec::from([
         //Throughput(threads)
@@ -136,6 +142,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Throughput(algorithm)
         ProfilingConfiguration {
@@ -149,6 +160,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         //CHT throughput and EPC paging
         ProfilingConfiguration {
@@ -165,6 +181,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             }]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         //CPU cycles per phase per algorithm
         ProfilingConfiguration {
@@ -178,6 +199,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version 2 vs. RHO
         ProfilingConfiguration {
@@ -191,6 +217,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version 2 vs. Version 3
         ProfilingConfiguration {
@@ -204,6 +235,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version 4 multi-threading
         ProfilingConfiguration {
@@ -217,6 +253,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version 4 multi-threading + context-switches
         ProfilingConfiguration {
@@ -230,6 +271,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version 4 and 5 multi-threading + context-switches
         ProfilingConfiguration {
@@ -243,6 +289,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
         // Version comparison
         ProfilingConfiguration {
@@ -256,6 +307,11 @@ pub fn hardcoded_profiling_jobs() -> Vec<Job> {
             datasets: HashSet::from([Dataset::CacheFit, Dataset::CacheExceed]),
             platforms: HashSet::from([Platform::Sgx]),
             sort_data: false,
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+            include_historical: true,
+            profiles: HashSet::from([BuildProfile::default()]),
         },
     ]);
     let jobs: Vec<Job> = configs
@@ -490,7 +546,8 @@ pub fn hardcoded_perf_report_commands(
 use crate::commit::{Commit, CompilationStatus, Operator, UploadCommitFormState};
 use crate::data_types::Algorithm::*;
 use crate::data_types::{
-    Dataset, ExperimentType, Job, Measurement, Parameter, ProfilingConfiguration,
+    BuildProfile, Dataset, ExperimentType, Job, Measurement, Parameter, ProfilingConfiguration,
+    DEFAULT_PROFILE, DEFAULT_REPETITIONS, DEFAULT_RUN_TIMEOUT,
 };
 use indoc::indoc;
 use time::OffsetDateTime;
@@ -677,6 +734,10 @@ pub fn predefined_commit() -> Commit {
                     exp_type: Throughput,
                     dataset: CacheFit,
                     baseline: Rho,
+                    repetitions: DEFAULT_REPETITIONS,
+                    run_timeout: DEFAULT_RUN_TIMEOUT,
+                    job_timeout: None,
+                    native_only: false,
                 }),
                 results: vec![
                     (
@@ -698,6 +759,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("algorithm".to_string(), "RHO".to_string()),
@@ -733,6 +795,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             (
@@ -771,6 +834,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("algorithm".to_string(), "RHO".to_string()),
@@ -806,6 +870,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("throughput".to_string(), "14.9952".to_string()),
@@ -827,6 +892,7 @@ pub fn predefined_commit() -> Commit {
                     ),
                 ],
                 findings: vec![],
+                statistics: vec![],
             },
             ExperimentChart {
                 config: PerfReport(PerfReportConfig {
@@ -834,6 +900,10 @@ pub fn predefined_commit() -> Commit {
                     exp_type: Throughput,
                     dataset: CacheExceed,
                     baseline: Rho,
+                    repetitions: DEFAULT_REPETITIONS,
+                    run_timeout: DEFAULT_RUN_TIMEOUT,
+                    job_timeout: None,
+                    native_only: false,
                 }),
                 results: vec![
                     (
@@ -855,6 +925,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("totalTime".to_string(), "2468680".to_string()),
@@ -890,6 +961,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase1Cycles".to_string(), "0".to_string()),
@@ -928,6 +1000,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("throughput".to_string(), "12.9637".to_string()),
@@ -966,6 +1039,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("cyclesPerTuple".to_string(), "14".to_string()),
@@ -984,6 +1058,7 @@ pub fn predefined_commit() -> Commit {
                     ),
                 ],
                 findings: vec![],
+                statistics: vec![],
             },
             ExperimentChart {
                 config: PerfReport(PerfReportConfig {
@@ -991,6 +1066,10 @@ pub fn predefined_commit() -> Commit {
                     exp_type: Scalability,
                     dataset: CacheFit,
                     baseline: Rho,
+                    repetitions: DEFAULT_REPETITIONS,
+                    run_timeout: DEFAULT_RUN_TIMEOUT,
+                    job_timeout: None,
+                    native_only: false,
                 }),
                 results: vec![
                     (
@@ -1012,6 +1091,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("throughput".to_string(), "39.6187".to_string()),
@@ -1047,6 +1127,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             (
@@ -1085,6 +1166,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase2Cycles".to_string(), "0".to_string()),
@@ -1123,6 +1205,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("algorithm".to_string(), "RHO".to_string()),
@@ -1158,6 +1241,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "5242880".to_string()),
@@ -1193,6 +1277,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "5242880".to_string()),
@@ -1228,6 +1313,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase2Time".to_string(), "5495".to_string()),
@@ -1266,6 +1352,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "5242880".to_string()),
@@ -1304,6 +1391,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relR".to_string(), "1310720".to_string()),
@@ -1342,6 +1430,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("cyclesPerTuple".to_string(), "226".to_string()),
@@ -1380,6 +1469,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("cyclesPerTuple".to_string(), "226".to_string()),
@@ -1418,6 +1508,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("cyclesPerTuple".to_string(), "217".to_string()),
@@ -1453,6 +1544,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase1Time".to_string(), "0".to_string()),
@@ -1488,6 +1580,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relR".to_string(), "1310720".to_string()),
@@ -1523,6 +1616,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("throughput".to_string(), "14.9652".to_string()),
@@ -1561,6 +1655,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("totalTime".to_string(), "322788".to_string()),
@@ -1579,6 +1674,7 @@ pub fn predefined_commit() -> Commit {
                     ),
                 ],
                 findings: vec![],
+                statistics: vec![],
             },
             ExperimentChart {
                 config: PerfReport(PerfReportConfig {
@@ -1586,6 +1682,10 @@ pub fn predefined_commit() -> Commit {
                     exp_type: Scalability,
                     dataset: CacheExceed,
                     baseline: Rho,
+                    repetitions: DEFAULT_REPETITIONS,
+                    run_timeout: DEFAULT_RUN_TIMEOUT,
+                    job_timeout: None,
+                    native_only: false,
                 }),
                 results: vec![
                     (
@@ -1607,6 +1707,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("algorithm".to_string(), "RHO".to_string()),
@@ -1642,6 +1743,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase2Cycles".to_string(), "348332420".to_string()),
@@ -1677,6 +1779,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase1Time".to_string(), "5008557".to_string()),
@@ -1715,6 +1818,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             (
@@ -1753,6 +1857,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("threads".to_string(), "1".to_string()),
@@ -1791,6 +1896,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relR".to_string(), "13107200".to_string()),
@@ -1829,6 +1935,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "52428800".to_string()),
@@ -1867,6 +1974,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "52428800".to_string()),
@@ -1902,6 +2010,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "52428800".to_string()),
@@ -1937,6 +2046,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "52428800".to_string()),
@@ -1972,6 +2082,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("phase2Time".to_string(), "55665".to_string()),
@@ -2010,6 +2121,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("matches".to_string(), "52428800".to_string()),
@@ -2048,6 +2160,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("throughput".to_string(), "17.9746".to_string()),
@@ -2083,6 +2196,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("threads".to_string(), "2".to_string()),
@@ -2118,6 +2232,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("relS".to_string(), "52428800".to_string()),
@@ -2156,6 +2271,7 @@ pub fn predefined_commit() -> Commit {
                             sort_r: false,
                             sort_s: false,
                             csv: true,
+                            profile: DEFAULT_PROFILE.to_string(),
                         },
                         Ok(HashMap::from([
                             ("cyclesPerTuple".to_string(), "122".to_string()),
@@ -2174,6 +2290,7 @@ pub fn predefined_commit() -> Commit {
                     ),
                 ],
                 findings: vec![],
+                statistics: vec![],
             },
         ],
         findings: vec![],
@@ -2,6 +2,9 @@ pub mod commandline;
 pub mod commit;
 pub mod data_types;
 pub mod hardcoded;
+pub mod output_schema;
+pub mod queue;
+pub mod statistics;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -0,0 +1,154 @@
+//! The columns of the CSV that TeeBench prints after each run.
+//!
+//! This is the only place that needs to change if TeeBench's output changes: The backend creates and extends its `output` table from [`OUTPUT_COLUMNS`], and the frontend looks up measurements by these names.
+
+/// SQL type of an output column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Integer,
+    Real,
+    Text,
+}
+
+impl ColumnType {
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Self::Integer => "INTEGER",
+            Self::Real => "REAL",
+            Self::Text => "TEXT",
+        }
+    }
+    /// Guesses the type of a column that is not in the schema from one of its values.
+    pub fn infer(value: &str) -> Self {
+        if value.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if value.parse::<f64>().is_ok() {
+            Self::Real
+        } else {
+            Self::Text
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputColumn {
+    pub name: &'static str,
+    pub sql_type: ColumnType,
+    /// TeeBench always prints required columns. Optional ones depend on the platform and the compile flags (eg. PCM counters).
+    pub required: bool,
+}
+
+impl OutputColumn {
+    const fn required(name: &'static str, sql_type: ColumnType) -> Self {
+        Self {
+            name,
+            sql_type,
+            required: true,
+        }
+    }
+    const fn optional(name: &'static str, sql_type: ColumnType) -> Self {
+        Self {
+            name,
+            sql_type,
+            required: false,
+        }
+    }
+    /// Column definition for `CREATE TABLE` and `ALTER TABLE ... ADD COLUMN`.
+    pub fn to_sql(&self) -> String {
+        if self.required {
+            format!("{} {} NOT NULL", self.name, self.sql_type.to_sql())
+        } else {
+            format!("{} {}", self.name, self.sql_type.to_sql())
+        }
+    }
+}
+
+use ColumnType::{Integer, Real, Text};
+
+/// All columns TeeBench prints, in the order of its CSV output.
+pub const OUTPUT_COLUMNS: &[OutputColumn] = &[
+    OutputColumn::required("algorithm", Text),
+    OutputColumn::required("threads", Integer),
+    OutputColumn::required("relR", Integer),
+    OutputColumn::required("relS", Integer),
+    OutputColumn::required("matches", Integer),
+    OutputColumn::required("phase1Cycles", Integer),
+    OutputColumn::required("phase2Cycles", Integer),
+    OutputColumn::required("cyclesPerTuple", Integer),
+    OutputColumn::required("phase1Time", Integer),
+    OutputColumn::required("phase2Time", Integer),
+    OutputColumn::required("totalTime", Integer),
+    OutputColumn::required("throughput", Real),
+    OutputColumn::optional("phase1L3CacheMisses", Integer),
+    OutputColumn::optional("phase1L3HitRatio", Real),
+    OutputColumn::optional("phase1L2CacheMisses", Integer),
+    OutputColumn::optional("phase1L2HitRatio", Real),
+    OutputColumn::optional("phase1IPC", Real),
+    OutputColumn::optional("phase1IR", Integer),
+    OutputColumn::optional("phase1EWB", Integer),
+    OutputColumn::optional("phase1VoluntaryCS", Integer),
+    OutputColumn::optional("phase1InvoluntaryCS", Integer),
+    OutputColumn::optional("phase1UserCpuTime", Integer),
+    OutputColumn::optional("phase1SystemCpuTime", Integer),
+    OutputColumn::optional("phase2L3CacheMisses", Integer),
+    OutputColumn::optional("phase2L3HitRatio", Real),
+    OutputColumn::optional("phase2L2CacheMisses", Integer),
+    OutputColumn::optional("phase2L2HitRatio", Real),
+    OutputColumn::optional("phase2IPC", Real),
+    OutputColumn::optional("phase2IR", Integer),
+    OutputColumn::optional("phase2EWB", Integer),
+    OutputColumn::optional("phase2VoluntaryCS", Integer),
+    OutputColumn::optional("phase2InvoluntaryCS", Integer),
+    OutputColumn::optional("phase2UserCpuTime", Integer),
+    OutputColumn::optional("phase2SystemCpuTime", Integer),
+    OutputColumn::optional("totalL3CacheMisses", Integer),
+    OutputColumn::optional("totalL3HitRatio", Real),
+    OutputColumn::optional("totalL2CacheMisses", Integer),
+    OutputColumn::optional("totalL2HitRatio", Real),
+    OutputColumn::optional("totalIPC", Real),
+    OutputColumn::optional("totalIR", Integer),
+    OutputColumn::optional("totalEWB", Integer),
+    OutputColumn::optional("totalVoluntaryCS", Integer),
+    OutputColumn::optional("totalInvoluntaryCS", Integer),
+    OutputColumn::optional("totalUserCpuTime", Integer),
+    OutputColumn::optional("totalSystemCpuTime", Integer),
+];
+
+pub fn get_column(name: &str) -> Option<&'static OutputColumn> {
+    OUTPUT_COLUMNS.iter().find(|c| c.name == name)
+}
+
+/// Column names are put into SQL statements unescaped, so only allow what TeeBench uses.
+pub fn is_valid_column_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::Measurement;
+    use strum::VariantNames;
+
+    #[test]
+    fn measurements_are_in_schema() {
+        for name in Measurement::VARIANTS {
+            let m: Measurement = name.parse().unwrap();
+            if let Some(column) = m.column() {
+                assert!(
+                    get_column(column).is_some(),
+                    "{column} is not in the schema"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn column_names_are_valid() {
+        for c in OUTPUT_COLUMNS {
+            assert!(is_valid_column_name(c.name));
+        }
+        assert!(!is_valid_column_name("throughput; DROP TABLE output"));
+    }
+}
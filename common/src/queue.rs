@@ -0,0 +1,125 @@
+//! Ordering of the job queue. The backend and the frontend apply the same operations to their copy of the queue, so they agree on the order.
+//!
+//! The queue is sorted by `JobPriority`, highest first. Within a priority class, jobs keep the order they were submitted or moved to.
+
+use std::collections::VecDeque;
+
+use crate::data_types::{Job, JobIdType, JobPriority};
+
+/// Appends `job` behind the last job of its priority class.
+pub fn enqueue(queue: &mut VecDeque<Job>, job: Job) {
+    let position = queue
+        .iter()
+        .position(|j| j.priority < job.priority)
+        .unwrap_or(queue.len());
+    queue.insert(position, job);
+}
+
+/// Moves the job with `id` to `position`, but not past the jobs of another priority class.
+///
+/// Returns `false` if there is no such job.
+pub fn move_job(queue: &mut VecDeque<Job>, id: &JobIdType, position: usize) -> bool {
+    let Some(from) = queue.iter().position(|j| j.id == *id) else {
+        return false;
+    };
+    let job = queue.remove(from).unwrap();
+    let first = queue
+        .iter()
+        .position(|j| j.priority <= job.priority)
+        .unwrap_or(queue.len());
+    let end = queue
+        .iter()
+        .position(|j| j.priority < job.priority)
+        .unwrap_or(queue.len());
+    queue.insert(position.clamp(first, end), job);
+    true
+}
+
+/// Changes the priority of the job with `id`. It moves behind the other jobs of its new priority class.
+///
+/// Returns `false` if there is no such job.
+pub fn set_priority(queue: &mut VecDeque<Job>, id: &JobIdType, priority: JobPriority) -> bool {
+    let Some(from) = queue.iter().position(|j| j.id == *id) else {
+        return false;
+    };
+    let mut job = queue.remove(from).unwrap();
+    job.priority = priority;
+    enqueue(queue, job);
+    true
+}
+
+/// Makes the job with `id` the next one to start: it gets the highest priority and moves to the front.
+///
+/// Returns `false` if there is no such job.
+pub fn bump_job(queue: &mut VecDeque<Job>, id: &JobIdType) -> bool {
+    set_priority(queue, id, JobPriority::High) && move_job(queue, id, 0)
+}
+
+/// The order and priorities of `queue`, as sent with `ServerMessage::QueueOrder`.
+pub fn queue_order(queue: &VecDeque<Job>) -> Vec<(JobIdType, JobPriority)> {
+    queue.iter().map(|j| (j.id, j.priority)).collect()
+}
+
+/// Reorders `queue` like `order`. Jobs that are not in `order` keep their relative order at the end.
+pub fn apply_queue_order(queue: &mut VecDeque<Job>, order: &[(JobIdType, JobPriority)]) {
+    for j in queue.iter_mut() {
+        if let Some((_, priority)) = order.iter().find(|(id, _)| *id == j.id) {
+            j.priority = *priority;
+        }
+    }
+    queue.make_contiguous().sort_by_key(|j| {
+        order
+            .iter()
+            .position(|(id, _)| *id == j.id)
+            .unwrap_or(order.len())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(priority: JobPriority) -> Job {
+        Job {
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn ids(queue: &VecDeque<Job>) -> Vec<JobIdType> {
+        queue.iter().map(|j| j.id).collect()
+    }
+
+    #[test]
+    fn test_queue_priorities() {
+        let (low, normal_1, normal_2, high) = (
+            job(JobPriority::Low),
+            job(JobPriority::Normal),
+            job(JobPriority::Normal),
+            job(JobPriority::High),
+        );
+        let mut queue = VecDeque::new();
+        for j in [&low, &normal_1, &high, &normal_2] {
+            enqueue(&mut queue, j.clone());
+        }
+        assert_eq!(ids(&queue), [high.id, normal_1.id, normal_2.id, low.id]);
+        // Moving stays within the priority class.
+        assert!(move_job(&mut queue, &normal_2.id, 0));
+        assert_eq!(ids(&queue), [high.id, normal_2.id, normal_1.id, low.id]);
+        assert!(move_job(&mut queue, &high.id, 3));
+        assert_eq!(ids(&queue), [high.id, normal_2.id, normal_1.id, low.id]);
+        assert!(set_priority(&mut queue, &normal_2.id, JobPriority::Low));
+        assert_eq!(ids(&queue), [high.id, normal_1.id, low.id, normal_2.id]);
+        assert!(bump_job(&mut queue, &normal_2.id));
+        assert_eq!(ids(&queue), [normal_2.id, high.id, normal_1.id, low.id]);
+        assert!(!bump_job(&mut queue, &JobIdType::new_v4()));
+
+        let order = queue_order(&queue);
+        let mut other: VecDeque<Job> = [&low, &normal_1, &high, &normal_2]
+            .into_iter()
+            .cloned()
+            .collect();
+        apply_queue_order(&mut other, &order);
+        assert_eq!(other, queue);
+    }
+}
@@ -0,0 +1,249 @@
+//! Aggregates the repetitions of a TeeBench run and compares them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Two-sided 95% quantiles of Student's t-distribution for 1 to 30 degrees of freedom.
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+/// Normal approximation for more than 30 degrees of freedom.
+const Z_95: f64 = 1.96;
+/// Differences with a larger p-value are treated as noise.
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Summary of the samples of one output column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub samples: Vec<f64>,
+    pub mean: f64,
+    pub median: f64,
+    /// Sample standard deviation (with Bessel's correction).
+    pub stddev: f64,
+    /// Half width of the 95% confidence interval of the mean.
+    pub ci95: f64,
+}
+
+impl Statistics {
+    /// Returns `None` for less than two samples, there is nothing to aggregate then.
+    pub fn new(samples: Vec<f64>) -> Option<Self> {
+        let n = samples.len();
+        if n < 2 {
+            return None;
+        }
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let mut sorted = samples.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        // Both indices are the same for an odd number of samples.
+        let median = (sorted[(n - 1) / 2] + sorted[n / 2]) / 2.0;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let stddev = variance.sqrt();
+        let t = T_95.get(n - 2).copied().unwrap_or(Z_95);
+        let ci95 = t * stddev / (n as f64).sqrt();
+        Some(Self {
+            samples,
+            mean,
+            median,
+            stddev,
+            ci95,
+        })
+    }
+    /// Lower and upper bound of the 95% confidence interval.
+    pub fn ci95_bounds(&self) -> (f64, f64) {
+        (self.mean - self.ci95, self.mean + self.ci95)
+    }
+    /// Welch's t-test of the means of `self` and `other`, which does not assume equal variances.
+    pub fn welch_t_test(&self, other: &Statistics) -> TTest {
+        let (n1, n2) = (self.samples.len() as f64, other.samples.len() as f64);
+        let (v1, v2) = (self.stddev.powi(2) / n1, other.stddev.powi(2) / n2);
+        let diff = self.mean - other.mean;
+        let pooled_stddev = ((self.stddev.powi(2) + other.stddev.powi(2)) / 2.0).sqrt();
+        let effect_size = if pooled_stddev > 0.0 {
+            diff / pooled_stddev
+        } else {
+            0.0
+        };
+        if v1 + v2 == 0.0 {
+            // Without any variance every difference is significant.
+            return TTest {
+                t: 0.0,
+                df: n1 + n2 - 2.0,
+                p_value: if diff == 0.0 { 1.0 } else { 0.0 },
+                effect_size,
+            };
+        }
+        let t = diff / (v1 + v2).sqrt();
+        let df = (v1 + v2).powi(2) / (v1.powi(2) / (n1 - 1.0) + v2.powi(2) / (n2 - 1.0));
+        TTest {
+            t,
+            df,
+            p_value: incomplete_beta(df / 2.0, 0.5, df / (df + t * t)),
+            effect_size,
+        }
+    }
+}
+
+/// Result of `Statistics::welch_t_test`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TTest {
+    pub t: f64,
+    /// Welch–Satterthwaite degrees of freedom.
+    pub df: f64,
+    /// Two-sided p-value.
+    pub p_value: f64,
+    /// Cohen's d, i.e. the difference of the means in pooled standard deviations.
+    pub effect_size: f64,
+}
+
+impl TTest {
+    pub fn is_significant(&self) -> bool {
+        self.p_value < SIGNIFICANCE_LEVEL
+    }
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |acc, (i, c)| {
+            acc + c / (x + 1.0 + i as f64)
+        });
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly only on this side, use the symmetry otherwise.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Evaluates the continued fraction of the incomplete beta function with Lentz's method.
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// Combines the outputs of several runs of the same command.
+///
+/// Returns the first sample with every numeric column replaced by the mean of all samples (rounded for integer columns, so they still parse as integers), and the statistics of the numeric columns. The statistics are empty for a single sample.
+pub fn aggregate(
+    samples: &[HashMap<String, String>],
+) -> Option<(HashMap<String, String>, HashMap<String, Statistics>)> {
+    let mut representative = samples.first()?.clone();
+    let mut statistics = HashMap::new();
+    for (column, value) in representative.iter_mut() {
+        let values: Option<Vec<&String>> = samples.iter().map(|s| s.get(column)).collect();
+        let Some(values) = values else {
+            continue;
+        };
+        let is_integer = values.iter().all(|v| v.parse::<i64>().is_ok());
+        let parsed: Option<Vec<f64>> = values.iter().map(|v| v.parse::<f64>().ok()).collect();
+        let Some(stats) = parsed.and_then(Statistics::new) else {
+            continue;
+        };
+        *value = if is_integer {
+            (stats.mean.round() as i64).to_string()
+        } else {
+            stats.mean.to_string()
+        };
+        statistics.insert(column.clone(), stats);
+    }
+    Some((representative, statistics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_of_samples() {
+        assert_eq!(Statistics::new(vec![1.0]), None);
+        let s = Statistics::new(vec![4.0, 1.0, 3.0, 2.0]).unwrap();
+        assert_eq!(s.mean, 2.5);
+        assert_eq!(s.median, 2.5);
+        assert!((s.stddev - 1.290_994).abs() < 1e-6);
+        assert!((s.ci95 - 3.182 * s.stddev / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn welch_t_test() {
+        let a = Statistics::new(vec![1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        let b = Statistics::new(vec![3.0, 4.0, 5.0, 6.0, 7.0]).unwrap();
+        let test = a.welch_t_test(&b);
+        assert!((test.t + 2.0).abs() < 1e-9);
+        assert!((test.df - 8.0).abs() < 1e-9);
+        assert!((test.p_value - 0.080_516).abs() < 1e-5);
+        assert!((test.effect_size + 1.264_911).abs() < 1e-5);
+        assert!(!test.is_significant());
+        let c = Statistics::new(vec![13.0, 14.0, 15.0, 16.0, 17.0]).unwrap();
+        let test = a.welch_t_test(&c);
+        assert!(test.is_significant());
+        assert!(test.p_value < 1e-5);
+        let constant = Statistics::new(vec![2.0, 2.0]).unwrap();
+        assert_eq!(constant.welch_t_test(&constant).p_value, 1.0);
+    }
+
+    #[test]
+    fn aggregate_keeps_integers() {
+        let sample = |throughput: &str, cycles: &str| {
+            HashMap::from([
+                ("algorithm".to_string(), "RHO".to_string()),
+                ("throughput".to_string(), throughput.to_string()),
+                ("totalCycles".to_string(), cycles.to_string()),
+            ])
+        };
+        let (rep, stats) = aggregate(&[sample("1.5", "10"), sample("2.5", "13")]).unwrap();
+        assert_eq!(rep["algorithm"], "RHO");
+        assert_eq!(rep["throughput"], "2");
+        assert_eq!(rep["totalCycles"], "12");
+        assert_eq!(stats["totalCycles"].mean, 11.5);
+        assert!(!stats.contains_key("algorithm"));
+        let (rep, stats) = aggregate(&[sample("1.5", "10")]).unwrap();
+        assert_eq!(rep["throughput"], "1.5");
+        assert!(stats.is_empty());
+        assert_eq!(aggregate(&[]), None);
+    }
+}
@@ -1,3 +1,38 @@
+// Draws the error bars in `dataset.errorBars`: One `[low, high]` pair (or null) per data point.
+const errorBarsPlugin = {
+    id: "errorBars",
+    afterDatasetsDraw(chart) {
+        const ctx = chart.ctx;
+        chart.data.datasets.forEach((dataset, i) => {
+            if (!dataset.errorBars || !chart.isDatasetVisible(i)) {
+                return;
+            }
+            const meta = chart.getDatasetMeta(i);
+            const yScale = chart.scales[meta.yAxisID];
+            ctx.save();
+            ctx.strokeStyle = "#444444";
+            ctx.lineWidth = 1.5;
+            meta.data.forEach((element, j) => {
+                const bar = dataset.errorBars[j];
+                if (!bar) {
+                    return;
+                }
+                const low = yScale.getPixelForValue(bar[0]);
+                const high = yScale.getPixelForValue(bar[1]);
+                ctx.beginPath();
+                ctx.moveTo(element.x, low);
+                ctx.lineTo(element.x, high);
+                ctx.moveTo(element.x - 4, low);
+                ctx.lineTo(element.x + 4, low);
+                ctx.moveTo(element.x - 4, high);
+                ctx.lineTo(element.x + 4, high);
+                ctx.stroke();
+            });
+            ctx.restore();
+        });
+    }
+};
+
 export class MyChart {
     chart;
     constructor() {
@@ -14,6 +49,7 @@ export class MyChart {
             this.chart.update();
         } else {
             //console.log("Creating new chart.");
+            obj_config.plugins = [errorBarsPlugin];
             this.chart = new Chart(
                 context,
                 obj_config
@@ -0,0 +1,307 @@
+use gloo_console::log;
+use gloo_net::http::{Method, Request};
+use time::macros::format_description;
+use time::OffsetDateTime;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+use crate::navigation::Navigation;
+
+use common::commit::CommitState;
+use common::data_types::{CacheStatistics, ResultUpload, REPLACE_ALG};
+
+/// Sends `DELETE /api/results?{query}` after asking the user. Afterwards, `status` holds the outcome and `reload` is incremented to fetch the statistics again.
+fn invalidate(query: String, status: UseStateHandle<Option<String>>, reload: UseStateHandle<u32>) {
+    let confirmed = web_sys::window()
+        .and_then(|w| {
+            w.confirm_with_message(&format!(
+                "Delete all cached results matching `{query}`? This cannot be undone."
+            ))
+            .ok()
+        })
+        .unwrap_or(false);
+    if !confirmed {
+        return;
+    }
+    spawn_local(async move {
+        let resp = Request::get(&format!("/api/results?{query}"))
+            .method(Method::DELETE)
+            .send()
+            .await
+            .expect("Server didn't respond. Is it running?");
+        let msg = if resp.ok() {
+            match resp.json::<serde_json::Value>().await {
+                Ok(json) => format!("Deleted {} run(s).", json["deleted"]),
+                Err(e) => format!("Invalid response: {e}"),
+            }
+        } else {
+            format!("Error: {}", resp.text().await.unwrap_or_default())
+        };
+        status.set(Some(msg));
+        reload.set(*reload + 1);
+    });
+}
+
+/// Sends the results of TeeBench runs outside of TeeBenchWeb to `POST /api/results`.
+fn upload(
+    upload: ResultUpload,
+    status: UseStateHandle<Option<String>>,
+    reload: UseStateHandle<u32>,
+) {
+    spawn_local(async move {
+        let resp = Request::get("/api/results")
+            .method(Method::POST)
+            .json(&upload)
+            .unwrap()
+            .send()
+            .await
+            .expect("Server didn't respond. Is it running?");
+        let msg = if resp.ok() {
+            match resp.json::<serde_json::Value>().await {
+                Ok(json) => format!("Imported {} run(s).", json["imported"]),
+                Err(e) => format!("Invalid response: {e}"),
+            }
+        } else {
+            format!("Error: {}", resp.text().await.unwrap_or_default())
+        };
+        status.set(Some(msg));
+        reload.set(*reload + 1);
+    });
+}
+
+fn encode(value: &str) -> String {
+    String::from(js_sys::encode_uri_component(value))
+}
+
+fn format_date(date: &Option<OffsetDateTime>) -> String {
+    let format = format_description!("[year]-[month]-[day] [hour]:[minute]");
+    match date {
+        Some(d) => d.format(format).unwrap(),
+        None => "csv import".to_string(),
+    }
+}
+
+#[function_component]
+pub fn Cache() -> Html {
+    let commit_store = use_store_value::<CommitState>();
+    let statistics = use_state(|| None::<Vec<CacheStatistics>>);
+    let status = use_state(|| None::<String>);
+    // Incremented to fetch the statistics again.
+    let reload = use_state(|| 0u32);
+    {
+        let statistics = statistics.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    let resp: Result<Vec<CacheStatistics>, _> = Request::get("/api/cache")
+                        .method(Method::GET)
+                        .send()
+                        .await
+                        .expect("Server didn't respond. Is it running?")
+                        .json()
+                        .await;
+                    match resp {
+                        Ok(json) => statistics.set(Some(json)),
+                        Err(e) => log!("Error getting cache statistics json: ", e.to_string()),
+                    }
+                });
+                || ()
+            },
+            *reload,
+        );
+    }
+
+    let rows: Vec<Html> = statistics
+        .iter()
+        .flatten()
+        .map(|s| {
+            let query = format!(
+                "app_name={}&algorithm={}",
+                encode(&s.platform),
+                encode(&s.algorithm)
+            );
+            let onclick = {
+                let (status, reload) = (status.clone(), reload.clone());
+                Callback::from(move |_| invalidate(query.clone(), status.clone(), reload.clone()))
+            };
+            html! {
+                <tr>
+                    <td>{s.platform.clone()}</td>
+                    <td>{s.algorithm.clone()}</td>
+                    <td>{s.runs.to_string()}</td>
+                    <td>{s.builds.to_string()}</td>
+                    <td>{format_date(&s.oldest)}</td>
+                    <td>{format_date(&s.newest)}</td>
+                    <td>
+                        <button class="btn btn-sm btn-danger" {onclick}>
+                            <i class="bi-trash"></i>
+                        </button>
+                    </td>
+                </tr>
+            }
+        })
+        .collect();
+    let total_runs: usize = statistics.iter().flatten().map(|s| s.runs).sum();
+
+    let commit_ref = use_node_ref();
+    let onclick_commit = {
+        let (status, reload) = (status.clone(), reload.clone());
+        let commit_ref = commit_ref.clone();
+        Callback::from(move |_| {
+            let id = commit_ref.cast::<HtmlSelectElement>().unwrap().value();
+            invalidate(format!("commit={id}"), status.clone(), reload.clone());
+        })
+    };
+    let commit_options: Vec<Html> = commit_store
+        .0
+        .iter()
+        .map(|c| html! { <option value={c.id.to_string()}>{c.get_title()}</option> })
+        .collect();
+    let upload_commit_options = commit_options.clone();
+    let fingerprint_ref = use_node_ref();
+    let onclick_fingerprint = {
+        let (status, reload) = (status.clone(), reload.clone());
+        let fingerprint_ref = fingerprint_ref.clone();
+        Callback::from(move |_| {
+            let fingerprint = fingerprint_ref.cast::<HtmlInputElement>().unwrap().value();
+            if !fingerprint.is_empty() {
+                let query = format!("fingerprint={}", encode(fingerprint.trim()));
+                invalidate(query, status.clone(), reload.clone());
+            }
+        })
+    };
+    let days_ref = use_node_ref();
+    let onclick_prune = {
+        let (status, reload) = (status.clone(), reload.clone());
+        let days_ref = days_ref.clone();
+        Callback::from(move |_| {
+            let days = days_ref.cast::<HtmlInputElement>().unwrap().value();
+            if let Ok(days) = days.parse::<u32>() {
+                invalidate(
+                    format!("older_than_days={days}"),
+                    status.clone(),
+                    reload.clone(),
+                );
+            }
+        })
+    };
+
+    let command_ref = use_node_ref();
+    let csv_ref = use_node_ref();
+    let source_ref = use_node_ref();
+    let upload_commit_ref = use_node_ref();
+    let onclick_upload = {
+        let (status, reload) = (status.clone(), reload.clone());
+        let (command_ref, csv_ref) = (command_ref.clone(), csv_ref.clone());
+        let (source_ref, upload_commit_ref) = (source_ref.clone(), upload_commit_ref.clone());
+        Callback::from(move |_| {
+            let commit = upload_commit_ref
+                .cast::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let result_upload = ResultUpload {
+                command: command_ref.cast::<HtmlInputElement>().unwrap().value(),
+                csv: csv_ref.cast::<HtmlTextAreaElement>().unwrap().value(),
+                source: source_ref.cast::<HtmlInputElement>().unwrap().value(),
+                commit: commit.parse().ok(),
+            };
+            upload(result_upload, status.clone(), reload.clone());
+        })
+    };
+
+    let status_html = match &*status {
+        Some(msg) => html! { <div class="alert alert-info" role="alert">{msg.clone()}</div> },
+        None => html! {},
+    };
+    html! {
+        <div class="container-fluid">
+            <div class="row vh-100">
+                <div class="col-12 col-sm-3 col-xl-2 px-sm-2 px-0 bg-dark d-flex sticky-top">
+                    <Navigation active_nav_item={"Cache"} />
+                </div>
+                <div class="col d-flex flex-column h-sm-100">
+                    <main class="row">
+                        <div class="col pt-4 col-lg-8">
+                            <h2>{"Result Cache"}</h2>
+                            {status_html}
+                            <form class="row g-3 mb-4">
+                                <div class="col-md">
+                                    <label class="form-label" for="tbw-cache-commit">{"Operator"}</label>
+                                    <div class="input-group">
+                                        <select class="form-select" id="tbw-cache-commit" ref={commit_ref}>
+                                            {for commit_options}
+                                        </select>
+                                        <button class="btn btn-danger" type="button" onclick={onclick_commit} disabled={commit_store.0.is_empty()}>{"Invalidate"}</button>
+                                    </div>
+                                </div>
+                                <div class="col-md">
+                                    <label class="form-label" for="tbw-cache-fingerprint">{"TeeBench build fingerprint"}</label>
+                                    <div class="input-group">
+                                        <input class="form-control" type="text" id="tbw-cache-fingerprint" ref={fingerprint_ref} />
+                                        <button class="btn btn-danger" type="button" onclick={onclick_fingerprint}>{"Invalidate"}</button>
+                                    </div>
+                                </div>
+                                <div class="col-md">
+                                    <label class="form-label" for="tbw-cache-days">{"Older than (days)"}</label>
+                                    <div class="input-group">
+                                        <input class="form-control" type="number" min="0" value="30" id="tbw-cache-days" ref={days_ref} />
+                                        <button class="btn btn-danger" type="button" onclick={onclick_prune}>{"Prune"}</button>
+                                    </div>
+                                </div>
+                            </form>
+                            <h4>{"Import results"}</h4>
+                            <form class="row g-3 mb-4">
+                                <div class="col-md-8">
+                                    <label class="form-label" for="tbw-import-command">{"TeeBench command line"}</label>
+                                    <input class="form-control font-monospace" type="text" id="tbw-import-command" placeholder="./app -a RHO -d cache-fit -n 2" ref={command_ref} />
+                                </div>
+                                <div class="col-md-4">
+                                    <label class="form-label" for="tbw-import-source">{"Source"}</label>
+                                    <input class="form-control" type="text" id="tbw-import-source" placeholder="Machine name" ref={source_ref} />
+                                </div>
+                                <div class="col-12">
+                                    <label class="form-label" for="tbw-import-csv">{"CSV output"}</label>
+                                    <textarea class="form-control font-monospace" id="tbw-import-csv" rows="5" ref={csv_ref}></textarea>
+                                </div>
+                                <div class="col-md-8">
+                                    <label class="form-label" for="tbw-import-commit">{format!("Operator (for `-a {REPLACE_ALG}`)")}</label>
+                                    <select class="form-select" id="tbw-import-commit" ref={upload_commit_ref}>
+                                        <option value="" selected=true>{"None"}</option>
+                                        {for upload_commit_options}
+                                    </select>
+                                </div>
+                                <div class="col-md-4 d-flex align-items-end">
+                                    <button class="btn btn-primary" type="button" onclick={onclick_upload}>{"Import"}</button>
+                                </div>
+                            </form>
+                            <table class="table table-sm table-hover">
+                                <thead>
+                                    <tr>
+                                        <th>{"Platform"}</th>
+                                        <th>{"Algorithm"}</th>
+                                        <th>{"Runs"}</th>
+                                        <th>{"Builds"}</th>
+                                        <th>{"Oldest"}</th>
+                                        <th>{"Newest"}</th>
+                                        <th></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {for rows}
+                                </tbody>
+                                <tfoot>
+                                    <tr>
+                                        <th colspan="2">{"Total"}</th>
+                                        <th colspan="5">{total_runs.to_string()}</th>
+                                    </tr>
+                                </tfoot>
+                            </table>
+                        </div>
+                    </main>
+                </div>
+            </div>
+        </div>
+    }
+}
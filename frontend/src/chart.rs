@@ -13,7 +13,7 @@ use common::commit::{CommitIdType, CommitState};
 use common::data_types::Algorithm::Commit;
 use common::data_types::{
     Algorithm, Dataset, ExperimentChart, ExperimentChartResult, ExperimentType, JobConfig,
-    Measurement, Parameter, Platform, SingleRunResult, TeebenchArgs,
+    Measurement, Parameter, Platform, SingleRunResult, TeebenchArgs, DEFAULT_PROFILE,
 };
 
 use crate::js_bindings::MyChart;
@@ -57,92 +57,107 @@ fn get_measurement_from_single_result(
     single_run: &SingleRunResult,
     measurement: &Measurement,
 ) -> String {
-    match measurement {
-        Measurement::TotalEpcPaging => single_run.as_ref().map(|m| m["totalEWB"].clone()).unwrap(),
-        Measurement::Throughput => single_run
-            .as_ref()
-            .map(|m| m["throughput"].clone())
-            .unwrap(),
-        Measurement::Phase1Cycles => single_run
-            .as_ref()
-            .map(|m| m["phase1Cycles"].clone())
-            .unwrap(),
-        Measurement::Phase2Cycles => single_run
-            .as_ref()
-            .map(|m| m["phase2Cycles"].clone())
-            .unwrap(),
-        Measurement::TotalCycles => single_run
-            .as_ref()
-            .map(|m| m["cyclesPerTuple"].clone())
-            .unwrap(),
-        Measurement::TotalL2HitRatio => single_run
-            .as_ref()
-            .map(|m| m["totalL2HitRatio"].clone())
-            .unwrap(),
-        Measurement::TotalL3HitRatio => single_run
-            .as_ref()
-            .map(|m| m["totalL3HitRatio"].clone())
-            .unwrap(),
-        Measurement::TotalL2CacheMisses => single_run
-            .as_ref()
-            .map(|m| m["totalL2CacheMisses"].clone())
-            .unwrap(),
-        Measurement::TotalL3CacheMisses => single_run
-            .as_ref()
-            .map(|m| m["totalL3CacheMisses"].clone())
-            .unwrap(),
-        Measurement::IPC => single_run.as_ref().map(|m| m["totalIPC"].clone()).unwrap(),
-        Measurement::IR => single_run.as_ref().map(|m| m["totalIR"].clone()).unwrap(),
-        Measurement::TotalVoluntaryCS => single_run
-            .as_ref()
-            .map(|m| m["totalVoluntaryCS"].clone())
-            .unwrap(),
-        Measurement::TotalInvoluntaryCS => single_run
-            .as_ref()
-            .map(|m| m["totalInvoluntaryCS"].clone())
-            .unwrap(),
-        Measurement::TotalUserCpuTime => single_run
-            .as_ref()
-            .map(|m| m["totalUserCpuTime"].clone())
-            .unwrap(),
-        Measurement::TotalSystemCpuTime => single_run
-            .as_ref()
-            .map(|m| m["totalSystemCpuTime"].clone())
-            .unwrap(),
-        Measurement::TwoPhasesCycles
-        | Measurement::ThroughputAndTotalEPCPaging
-        | Measurement::ThroughputAndContextSwitches
-        | Measurement::ContextSwitches => panic!("Should not ask for a single value"),
+    match measurement.column() {
+        Some(column) => single_run.as_ref().map(|m| m[column].clone()).unwrap(),
+        None => panic!("Should not ask for a single value"),
     }
 }
 
+fn get_parameter_value(
+    args: &TeebenchArgs,
+    result: &SingleRunResult,
+    parameter: &Parameter,
+) -> String {
+    match parameter {
+        Parameter::Threads => result.as_ref().map(|m| m["threads"].clone()).unwrap(),
+        Parameter::DataSkew => args.threads.to_string(),
+        Parameter::JoinSelectivity => args.selectivity.to_string(),
+        Parameter::Algorithms => result.as_ref().map(|m| m["algorithm"].clone()).unwrap(),
+        Parameter::OuterTableSize => args.x.unwrap().to_string(),
+    }
+}
+
+/// Appended to the label of a line or bar, so the results of different build profiles can be told apart.
+fn profile_suffix(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        String::new()
+    } else {
+        format!(" ({profile} build)")
+    }
+}
+
+/// The results by algorithm, platform, dataset and build profile.
 fn create_data_hashmap(
     results: &ExperimentChartResult,
     measurement: Measurement,
     parameter: Parameter,
-) -> HashMap<(Algorithm, Platform, Dataset), Vec<(String, String)>> {
+) -> HashMap<(Algorithm, Platform, Dataset, String), Vec<(String, String)>> {
     let mut data = HashMap::new();
     for (args, result) in results {
         let v = data
-            .entry((args.algorithm, args.app_name, args.dataset))
+            .entry((
+                args.algorithm,
+                args.app_name,
+                args.dataset,
+                args.profile.clone(),
+            ))
             .or_insert(vec![]);
-        let p = match parameter {
-            Parameter::Threads => result.as_ref().map(|m| m["threads"].clone()).unwrap(),
-            Parameter::DataSkew => args.threads.to_string(),
-            Parameter::JoinSelectivity => args.selectivity.to_string(),
-            Parameter::Algorithms => result.as_ref().map(|m| m["algorithm"].clone()).unwrap(),
-            Parameter::OuterTableSize => args.x.unwrap().to_string(),
-        };
+        let p = get_parameter_value(args, result, &parameter);
         let m = get_measurement_from_single_result(result, &measurement);
         v.push((p, m));
     }
     data
 }
 
+/// Like `create_data_hashmap`, but with the bounds of the 95% confidence interval instead of the value. Only contains repeated runs.
+fn create_error_bar_hashmap(
+    exp_chart: &ExperimentChart,
+    measurement: Measurement,
+    parameter: Parameter,
+) -> HashMap<(Algorithm, Platform, Dataset, String), Vec<(String, (f64, f64))>> {
+    let mut data = HashMap::new();
+    let Some(column) = measurement.column() else {
+        return data;
+    };
+    for (args, result) in &exp_chart.results {
+        if let Some(stats) = exp_chart.statistics_for(args, column) {
+            data.entry((
+                args.algorithm,
+                args.app_name,
+                args.dataset,
+                args.profile.clone(),
+            ))
+            .or_insert(vec![])
+            .push((
+                get_parameter_value(args, result, &parameter),
+                stats.ci95_bounds(),
+            ));
+        }
+    }
+    data
+}
+
+/// Bounds of the 95% confidence interval of the first result matching the arguments, see `ExperimentChart::get_result_statistics`.
+fn get_error_bar(
+    exp_chart: &ExperimentChart,
+    field: &str,
+    platform: Platform,
+    dataset: Dataset,
+    algorithm: Algorithm,
+    threads: Option<u8>,
+) -> Option<(f64, f64)> {
+    exp_chart
+        .get_result_statistics(field, platform, dataset, algorithm, threads, None, None)
+        .first()
+        .map(|s| s.ci95_bounds())
+}
+
 /// Returns: chart_type, labels: Json: Vec<&str>, datasets: Json<Vec<Obj<>>>, plugins: Json<Vec<Obj<>>>, scales: Json<Vec<Obj<>>>
+/// `alg_errors` are the error bars for `alg_data`, see `create_error_bar_hashmap`. Missing ones are not drawn.
 pub fn predefined_throughput_exp(
     alg_titles: Vec<String>,
     alg_data: Vec<Vec<f64>>,
+    alg_errors: Vec<Vec<Option<(f64, f64)>>>,
     d: Dataset,
 ) -> (
     &'static str,
@@ -154,11 +169,12 @@ pub fn predefined_throughput_exp(
     let chart_type = "bar";
     let labels = json!(["native", "sgx"]);
     let mut dataset_prep = vec![];
-    for ((title, data), color) in alg_titles.iter().zip(alg_data).zip(COLORS) {
+    for (i, ((title, data), color)) in alg_titles.iter().zip(alg_data).zip(COLORS).enumerate() {
         dataset_prep.push(json!({
             "label": title,
             "backgroundColor": color,
             "data": data,
+            "errorBars": alg_errors.get(i),
         }));
     }
     let datasets = json!(dataset_prep);
@@ -186,9 +202,11 @@ pub fn predefined_throughput_exp(
     (chart_type, labels, datasets, plugins, scales)
 }
 
+/// See `predefined_throughput_exp` for `alg_errors`.
 pub fn predefined_scalability_exp(
     alg_titles: Vec<String>,
     alg_data: Vec<Vec<f64>>,
+    alg_errors: Vec<Vec<Option<(f64, f64)>>>,
     d: Dataset,
 ) -> (
     &'static str,
@@ -200,10 +218,11 @@ pub fn predefined_scalability_exp(
     let chart_type = "line";
     let labels = json!([1, 2, 3, 4, 5, 6, 7, 8]);
     let mut dataset_prep = vec![];
-    for ((title, data), color) in alg_titles.iter().zip(alg_data).zip(COLORS) {
+    for (i, ((title, data), color)) in alg_titles.iter().zip(alg_data).zip(COLORS).enumerate() {
         dataset_prep.push(json!({
             "label": title,
             "data": data,
+            "errorBars": alg_errors.get(i),
             "backgroundColor": color,
             "borderColor": color,
             "yAxisID": "y",
@@ -359,7 +378,7 @@ fn prepare_perf_report_chart(
     };
 
     let chart_type;
-    let labels;
+    let mut labels;
     let datasets;
     let plugins;
     let scales;
@@ -383,9 +402,9 @@ fn prepare_perf_report_chart(
     } else {
         alg_titles.push(pr_conf.baseline.to_string());
     }
-    let (title, version) = match commit_store.get_by_id(&pr_conf.id) {
+    let (title, version, profile) = match commit_store.get_by_id(&pr_conf.id) {
         None => panic!("Commit not found!"),
-        Some(c) => (c.title.clone(), c.version.clone()),
+        Some(c) => (c.title.clone(), c.version.clone(), c.profile.name.clone()),
     };
     let algorithm: Algorithm = get_algorithm_by_title(title.as_str(), version.as_str(), pr_conf.id);
     match pr_conf.exp_type {
@@ -401,6 +420,7 @@ fn prepare_perf_report_chart(
                             Platform::Native,
                             pr_conf.dataset,
                         )
+                        .with_profile(&profile)
                 })
                 .unwrap()
                 .1
@@ -410,21 +430,31 @@ fn prepare_perf_report_chart(
                 Ok(map) => map["throughput"].parse().unwrap(),
                 Err(e) => bail!("Error parsing chart: {e}"),
             };
-            let sgx = exp_chart
-                .results
-                .iter()
-                .find(|&tuple| {
-                    tuple.0
-                        == TeebenchArgs::for_throughput(algorithm, Platform::Sgx, pr_conf.dataset)
-                })
-                .unwrap()
-                .1
-                .clone();
-            let sgx = match sgx {
-                Ok(map) => map["throughput"].parse().unwrap(),
-                Err(e) => bail!("Error parsing chart: {e}"),
-            };
-            alg_data.push(vec![native, sgx]);
+            let mut values = vec![native];
+            // Reports made without SGX only have native results.
+            if !pr_conf.native_only {
+                let sgx = exp_chart
+                    .results
+                    .iter()
+                    .find(|&tuple| {
+                        tuple.0
+                            == TeebenchArgs::for_throughput(
+                                algorithm,
+                                Platform::Sgx,
+                                pr_conf.dataset,
+                            )
+                            .with_profile(&profile)
+                    })
+                    .unwrap()
+                    .1
+                    .clone();
+                let sgx = match sgx {
+                    Ok(map) => map["throughput"].parse().unwrap(),
+                    Err(e) => bail!("Error parsing chart: {e}"),
+                };
+                values.push(sgx);
+            }
+            alg_data.push(values);
             alg_data.push({
                 let native = exp_chart
                     .results
@@ -436,6 +466,7 @@ fn prepare_perf_report_chart(
                                 Platform::Native,
                                 pr_conf.dataset,
                             )
+                            .with_profile(&profile)
                     })
                     .unwrap()
                     .1
@@ -444,24 +475,46 @@ fn prepare_perf_report_chart(
                     Ok(map) => map["throughput"].parse().unwrap(),
                     Err(e) => bail!("Error parsing chart: {e}"),
                 };
-                let sgx: f64 = exp_chart
-                    .get_result_values(
-                        "throughput",
-                        Platform::Sgx,
-                        pr_conf.dataset,
-                        pr_conf.baseline,
-                        None,
-                        None,
-                        None,
-                    )
-                    .unwrap()
-                    .into_iter()
-                    .next()
-                    .unwrap();
-                vec![native, sgx]
+                if pr_conf.native_only {
+                    vec![native]
+                } else {
+                    let sgx: f64 = exp_chart
+                        .get_result_values(
+                            "throughput",
+                            Platform::Sgx,
+                            pr_conf.dataset,
+                            pr_conf.baseline,
+                            None,
+                            None,
+                            None,
+                        )
+                        .unwrap()
+                        .into_iter()
+                        .next()
+                        .unwrap();
+                    vec![native, sgx]
+                }
             });
+            let platforms = match pr_conf.native_only {
+                true => vec![Platform::Native],
+                false => vec![Platform::Native, Platform::Sgx],
+            };
+            let alg_errors: Vec<Vec<_>> = [algorithm, pr_conf.baseline]
+                .into_iter()
+                .map(|alg| {
+                    platforms
+                        .iter()
+                        .map(|&pl| {
+                            get_error_bar(&exp_chart, "throughput", pl, pr_conf.dataset, alg, None)
+                        })
+                        .collect()
+                })
+                .collect();
             (chart_type, labels, datasets, plugins, scales) =
-                predefined_throughput_exp(alg_titles, alg_data, pr_conf.dataset);
+                predefined_throughput_exp(alg_titles, alg_data, alg_errors, pr_conf.dataset);
+            if pr_conf.native_only {
+                labels = json!(["native"]);
+            }
         }
         ExperimentType::Scalability => {
             let mut alg_data = vec![];
@@ -509,8 +562,25 @@ fn prepare_perf_report_chart(
                 }
                 res
             });
+            let alg_errors: Vec<Vec<_>> = [algorithm, pr_conf.baseline]
+                .into_iter()
+                .map(|alg| {
+                    (1..=8)
+                        .map(|threads| {
+                            get_error_bar(
+                                &exp_chart,
+                                "throughput",
+                                Platform::Sgx,
+                                pr_conf.dataset,
+                                alg,
+                                Some(threads),
+                            )
+                        })
+                        .collect()
+                })
+                .collect();
             (chart_type, labels, datasets, plugins, scales) =
-                predefined_scalability_exp(alg_titles, alg_data, pr_conf.dataset);
+                predefined_scalability_exp(alg_titles, alg_data, alg_errors, pr_conf.dataset);
         }
         ExperimentType::EpcPaging => {
             let alg_title = alg_titles[1].clone();
@@ -904,7 +974,8 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                     Measurement::TotalVoluntaryCS,
                                     conf.clone().parameter,
                                 );
-                                for ((alg, _platform, _dataset), data_value) in data.iter() {
+                                for ((alg, _platform, _dataset, profile), data_value) in data.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg);
                                     // compare the global label (steps) with the data_value results
@@ -918,7 +989,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("{alg} involuntary CS"),
+                                        "label": format!("{alg}{} involuntary CS", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -930,7 +1001,9 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                     }));
                                 }
 
-                                for ((alg, _platform, _dataset), data_value) in data2.iter() {
+                                for ((alg, _platform, _dataset, profile), data_value) in
+                                    data2.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg).to_string()
                                         + &"AA".to_string();
@@ -945,7 +1018,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("{alg} voluntary CS"),
+                                        "label": format!("{alg}{} voluntary CS", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -1023,7 +1096,8 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                             },
                                         }
                                 });
-                                for ((alg, _platform, _dataset), data_value) in data.iter() {
+                                for ((alg, _platform, _dataset, profile), data_value) in data.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg);
                                     // compare the global label (steps) with the data_value results
@@ -1037,7 +1111,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("{alg} involuntary CS"),
+                                        "label": format!("{alg}{} involuntary CS", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -1048,7 +1122,9 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                     }));
                                 }
 
-                                for ((alg, _platform, _dataset), data_value) in data2.iter() {
+                                for ((alg, _platform, _dataset, profile), data_value) in
+                                    data2.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg).to_string()
                                         + &"AA".to_string();
@@ -1063,7 +1139,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("{alg} voluntary CS"),
+                                        "label": format!("{alg}{} voluntary CS", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -1108,7 +1184,8 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                             },
                                         }
                                 });
-                                for ((alg, platform, _dataset), data_value) in data.iter() {
+                                for ((alg, platform, _dataset, profile), data_value) in data.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg);
                                     // compare the global label (steps) with the data_value results
@@ -1126,7 +1203,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("Phase 1 {alg} on {platform}"),
+                                        "label": format!("Phase 1 {alg} on {platform}{}", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -1136,7 +1213,8 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                     }));
                                 }
 
-                                for ((alg, platform, _dataset), data_value) in data2.iter() {
+                                for ((alg, platform, _dataset, profile), data_value) in data2.iter()
+                                {
                                     let alg = commit_store.get_title_by_algorithm(alg).unwrap();
                                     let alg_color = get_color_by_algorithm(&alg).to_string()
                                         + &"AA".to_string();
@@ -1155,7 +1233,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }
                                     }
                                     datasets_prep.push(json!({
-                                        "label": format!("Phase 2 {alg} on {platform}"),
+                                        "label": format!("Phase 2 {alg} on {platform}{}", profile_suffix(profile)),
                                         "data": values,
                                         "backgroundColor": alg_color,
                                         "borderColor": alg_color,
@@ -1171,6 +1249,11 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                     conf.clone().measurement,
                                     conf.clone().parameter,
                                 );
+                                let error_bars = create_error_bar_hashmap(
+                                    &exp_chart,
+                                    conf.clone().measurement,
+                                    conf.clone().parameter,
+                                );
                                 scales = json!({
                                     "y": {
                                         "ticks": {
@@ -1195,6 +1278,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                 match conf.parameter {
                                     Parameter::Algorithms => {
                                         let mut values: Vec<String> = vec![];
+                                        let mut errors = vec![];
                                         let mut colors: Vec<String> = vec![];
                                         for s in &steps {
                                             let alg_color =
@@ -1204,18 +1288,27 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                                 a.algorithm.to_string() == s.to_string()
                                             }) {
                                                 None => {}
-                                                Some((_, r)) => {
+                                                Some((a, r)) => {
                                                     let val = get_measurement_from_single_result(
                                                         r,
                                                         &conf.measurement,
                                                     );
                                                     values.push(val);
+                                                    errors.push(
+                                                        conf.measurement
+                                                            .column()
+                                                            .and_then(|c| {
+                                                                exp_chart.statistics_for(a, c)
+                                                            })
+                                                            .map(|s| s.ci95_bounds()),
+                                                    );
                                                 }
                                             }
                                         }
                                         datasets_prep.push(json!({
                                         "label": format!("Throughput"),
                                         "data": values,
+                                        "errorBars": errors,
                                         "backgroundColor": colors,
                                         "borderColor": colors,
                                         "yAxisID": "y",
@@ -1224,23 +1317,36 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                                         }));
                                     }
                                     _ => {
-                                        for ((alg, platform, _dataset), data_value) in data.iter() {
+                                        for ((alg, platform, dataset, profile), data_value) in
+                                            data.iter()
+                                        {
+                                            let alg_errors = error_bars.get(&(
+                                                *alg,
+                                                *platform,
+                                                *dataset,
+                                                profile.clone(),
+                                            ));
                                             let alg =
                                                 commit_store.get_title_by_algorithm(alg).unwrap();
                                             let alg_color = get_color_by_algorithm(&alg);
                                             // compare the global label (steps) with the data_value results
                                             // fill in with NULL if a data_value is missing, otherwise pass the result
                                             let mut values: Vec<String> = vec![];
+                                            let mut errors = vec![];
                                             for s in &steps {
                                                 let v = data_value.iter().find(|(x, _)| s == x);
                                                 match v {
                                                     None => values.push("NULL".to_string()),
                                                     Some(val) => values.push(val.1.clone()),
                                                 }
+                                                errors.push(alg_errors.and_then(|e| {
+                                                    e.iter().find(|(x, _)| s == x).map(|e| e.1)
+                                                }));
                                             }
                                             datasets_prep.push(json!({
-                                                "label": format!("Throughput {alg} on {platform}"),
+                                                "label": format!("Throughput {alg} on {platform}{}", profile_suffix(profile)),
                                                 "data": values,
+                                                "errorBars": errors,
                                                 "backgroundColor": alg_color,
                                                 "borderColor": alg_color,
                                                 "yAxisID": "y",
@@ -1339,8 +1445,12 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                         );
                         let mut alg_titles = vec![];
                         let mut alg_data = vec![];
-                        for ((alg, _platform, _dataset), value) in data {
-                            alg_titles.push(commit_store.get_title_by_algorithm(&alg).unwrap());
+                        for ((alg, _platform, _dataset, profile), value) in data {
+                            alg_titles.push(format!(
+                                "{}{}",
+                                commit_store.get_title_by_algorithm(&alg).unwrap(),
+                                profile_suffix(&profile)
+                            ));
                             alg_data.push(
                                 value
                                     .iter()
@@ -1352,6 +1462,7 @@ pub fn Chart(ChartProps { exp_chart }: &ChartProps) -> Html {
                         (chart_type, labels, datasets, plugins, scales) = predefined_throughput_exp(
                             alg_titles,
                             alg_data,
+                            vec![],
                             *conf.datasets.iter().next().unwrap(), // As this conf belongs to a `ExperimentChart` it can only have one dataset
                         );
                     }
@@ -7,20 +7,27 @@ use web_sys::{HtmlFormElement, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
+use crate::components::number::InputNumber;
 use crate::components::select::{InputSelect, SelectDataOption};
 use crate::components::tag::Tag;
 use crate::js_bindings::{diff2html_html, hljs_highlight, start_intro};
 use crate::modal::Modal;
 use crate::modal::ModalContent;
 use crate::navigation::Navigation;
+use crate::profiling::{format_timeout, parse_timeout};
 use crate::queue::QueueState;
 
 use common::commit::{
-    CommitState, CompilationStatus, Operator, PerfReportStatus, UploadCommitFormState,
+    CommitIdType, CommitState, CompilationStatus, Operator, PerfReportStatus, UploadCommitFormState,
 };
-use common::data_types::{Algorithm, Job, JobConfig, PerfReportConfig, VariantNames};
+use common::data_types::{
+    Algorithm, BuildProfileState, Job, JobConfig, OutputLine, PerfReportConfig, VariantNames,
+    DEFAULT_REPETITIONS, DEFAULT_RUN_TIMEOUT,
+};
+use common::queue::enqueue;
 
 use yew_router::components::Link;
 
@@ -111,6 +118,22 @@ fn UploadCommit() -> Html {
             };
         })
     };
+    let profile_store = use_store_value::<BuildProfileState>();
+    let profiles: Vec<SelectDataOption> = profile_store
+        .0
+        .iter()
+        .map(|p| SelectDataOption::new(p.name.clone(), p.name.clone(), true))
+        .collect();
+    let profiles_onchange = {
+        let dispatch = Dispatch::<UploadCommitFormState>::new();
+        dispatch.reduce_mut_callback_with(move |store, e: Event| {
+            let select_elem = e.target_unchecked_into::<HtmlSelectElement>();
+            let value = select_elem.value();
+            if let Some(p) = profile_store.0.iter().find(|p| p.name == value) {
+                store.profile = p.clone();
+            }
+        })
+    };
     let onclick_submit = {
         let (upload_commit_state, upload_commit_dispatch) = use_store::<UploadCommitFormState>();
         let dispatch = Dispatch::<CommitState>::new();
@@ -162,6 +185,7 @@ fn UploadCommit() -> Html {
     } else {
         vec![]
     };
+    let selected_profile = vec![upload_commit_store.profile.name.clone()];
     let upload_disabled = !upload_commit_store.verify();
     let entire_form_disabled = if cfg!(feature = "static") {
         true
@@ -196,6 +220,9 @@ fn UploadCommit() -> Html {
                     <div class="col-md">
                         <InputSelect options={algs} onchange={algs_onchange} label={"Baseline"} multiple={false} selected={selected_baseline} disabled={entire_form_disabled} />
                     </div>
+                    <div class="col-md">
+                        <InputSelect options={profiles} onchange={profiles_onchange} label={"Build profile"} multiple={false} selected={selected_profile} disabled={entire_form_disabled} />
+                    </div>
                     <div class="col-auto">
                         <input class="btn btn-primary" type="button" onclick={onclick_submit} disabled={upload_disabled} value={"Upload"} />
                     </div>
@@ -205,6 +232,66 @@ fn UploadCommit() -> Html {
     }
 }
 
+/// How the performance reports started with "Generate Report" run, see `PerfReportConfig`.
+#[derive(Debug, Clone, PartialEq, Store)]
+pub struct PerfReportSettings {
+    pub repetitions: u32,
+    pub run_timeout: Option<u64>,
+    pub job_timeout: Option<u64>,
+}
+
+impl Default for PerfReportSettings {
+    fn default() -> Self {
+        Self {
+            repetitions: DEFAULT_REPETITIONS,
+            run_timeout: DEFAULT_RUN_TIMEOUT,
+            job_timeout: None,
+        }
+    }
+}
+
+#[function_component]
+fn PerfReportSettingsForm() -> Html {
+    let (store, dispatch) = use_store::<PerfReportSettings>();
+    let repetitions_onchange = dispatch.reduce_mut_callback_with(|store, e: Event| {
+        let input_num = e.target_unchecked_into::<HtmlInputElement>();
+        match u32::from_str(&input_num.value()) {
+            Ok(value) if value > 0 => store.repetitions = value,
+            _ => input_num.set_value(&store.repetitions.to_string()),
+        }
+    });
+    let run_timeout_onchange = dispatch.reduce_mut_callback_with(|store, e: Event| {
+        let input_num = e.target_unchecked_into::<HtmlInputElement>();
+        match parse_timeout(&input_num.value()) {
+            Some(value) => store.run_timeout = value,
+            None => input_num.set_value(&format_timeout(store.run_timeout)),
+        }
+    });
+    let job_timeout_onchange = dispatch.reduce_mut_callback_with(|store, e: Event| {
+        let input_num = e.target_unchecked_into::<HtmlInputElement>();
+        match parse_timeout(&input_num.value()) {
+            Some(value) => store.job_timeout = value,
+            None => input_num.set_value(&format_timeout(store.job_timeout)),
+        }
+    });
+    html! {
+        <div class="row g-3 align-items-end mb-3">
+            <div class="col-auto">
+                <b>{"Performance reports:"}</b>
+            </div>
+            <div class="col-md">
+                <InputNumber label={"Repetitions"} onchange={repetitions_onchange} selected={store.repetitions.to_string()} disabled={false} info_popover={None} />
+            </div>
+            <div class="col-md">
+                <InputNumber label={"Run timeout (s)"} onchange={run_timeout_onchange} selected={format_timeout(store.run_timeout)} disabled={false} info_popover={None} />
+            </div>
+            <div class="col-md">
+                <InputNumber label={"Job timeout (s)"} onchange={job_timeout_onchange} selected={format_timeout(store.job_timeout)} disabled={false} info_popover={None} />
+            </div>
+        </div>
+    }
+}
+
 #[function_component]
 fn CommitsList() -> Html {
     let (_content_store, content_dispatch) = use_store::<ModalContent>();
@@ -245,7 +332,22 @@ fn CommitsList() -> Html {
         let commit_title = commit.get_title();
         let compile_status_view = match commit.compilation {
             CompilationStatus::Uncompiled => html! {"waiting to start compilation..."},
-            CompilationStatus::Compiling => html! {"compiling..."},
+            CompilationStatus::Compiling => {
+                let live_output_onclick = {
+                    let id = commit.id;
+                    content_dispatch.set_callback(move |_| {
+                        ModalContent::with_modal_skeleton(html! {
+                            <CompilerLog {id} />
+                        }, html! {{format!("Compiler output for {commit_title}")}})
+                    })
+                };
+                html! {
+                    <>
+                        {"compiling... "}
+                        <button class="btn btn-secondary" onclick={live_output_onclick} data-bs-toggle="modal" data-bs-target="#mainModal">{"Show Live Output"}</button>
+                    </>
+                }
+            }
             CompilationStatus::Successful(ref warnings) => {
                 if warnings.is_empty() {
                     html! {"Successfully compiled."}
@@ -297,7 +399,12 @@ fn CommitsList() -> Html {
                             let queue_dispatch = queue_dispatch.clone();
                             Box::pin(async move {
                                 // TODO Passing fit in here and disregarding _exceed?
-                                let (fit, _exceed) = PerfReportConfig::for_throughput(id, baseline);
+                                let (mut fit, _exceed) = PerfReportConfig::for_throughput(id, baseline);
+                                // The backend applies these to every chart of the report.
+                                let settings = Dispatch::<PerfReportSettings>::new().get();
+                                fit.repetitions = settings.repetitions;
+                                fit.run_timeout = settings.run_timeout;
+                                fit.job_timeout = settings.job_timeout;
                                 let perf_report_job = Job::new(JobConfig::PerfReport(fit), OffsetDateTime::now_utc());
                                 let _resp = Request::get("/api/job")
                                     .method(Method::POST)
@@ -313,7 +420,7 @@ fn CommitsList() -> Html {
                                     }
                                 }
                                 queue_dispatch.reduce_mut(|queue| {
-                                    queue.queue.push_back(perf_report_job);
+                                    enqueue(&mut queue.queue, perf_report_job);
                                 });
                             })
                         })
@@ -349,6 +456,16 @@ fn CommitsList() -> Html {
                 alg => alg.to_string(),
             }
         };
+        let profile_tag = if commit.profile.is_default() {
+            html! {}
+        } else {
+            html! {
+                <div class="p-2">
+                    {"Build profile: "}
+                    <Tag text={commit.profile.name.clone()} />
+                </div>
+            }
+        };
         let diff_button = {
             if let Some(diff) = diff {
                 let diff = diff.clone();
@@ -394,6 +511,7 @@ fn CommitsList() -> Html {
                         {"Baseline: "}
                         <Tag text={baseline} />
                     </div>
+                    {profile_tag}
                 </div>
             </li>
         })
@@ -467,6 +585,7 @@ pub fn Commits() -> Html {
 
                             <h2>{"Operators"}</h2>
                             <UploadCommit />
+                            <PerfReportSettingsForm />
                             <CommitsList />
                         </div>
                     </main>
@@ -477,6 +596,31 @@ pub fn Commits() -> Html {
     }
 }
 
+/// Output of the builds that ran since the page was loaded, by operator. See `ServerMessage::CompilerOutput`.
+#[derive(Debug, Clone, Default, PartialEq, Store)]
+pub struct CompilerLogState(pub HashMap<CommitIdType, Vec<OutputLine>>);
+
+#[derive(PartialEq, Properties)]
+pub struct CompilerLogProps {
+    pub id: CommitIdType,
+}
+
+/// Shows the build of an operator while it runs, stderr in red.
+#[function_component]
+pub fn CompilerLog(CompilerLogProps { id }: &CompilerLogProps) -> Html {
+    let log_store = use_store_value::<CompilerLogState>();
+    let lines = log_store.0.get(id).into_iter().flatten().map(|l| match l {
+        OutputLine::Command(c) => html! { <div class="fw-bold">{format!("$ {c}")}</div> },
+        OutputLine::Stdout(l) => html! { <div>{l}</div> },
+        OutputLine::Stderr(l) => html! { <div class="text-danger">{l}</div> },
+    });
+    html! {
+        <pre>
+            {for lines}
+        </pre>
+    }
+}
+
 use crate::components::websocket::WebsocketState;
 use common::data_types::{ClientMessage, JobIdType};
 #[derive(PartialEq, Properties)]
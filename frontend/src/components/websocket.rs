@@ -5,13 +5,16 @@ use yewdux::prelude::*;
 
 use common::commit::CompilationStatus;
 use common::data_types::{
-    ClientMessage, Job, JobConfig, JobResult, JobStatus, PerfReportConfig, ServerMessage,
+    BuildProfile, BuildProfileState, ClientMessage, Job, JobConfig, JobHistoryPage, JobResult, JobStatus, PerfReportConfig,
+    Platform, ProfilingConfiguration, ServerMessage, ServerStatus,
 };
+use common::queue::apply_queue_order;
 use futures::{SinkExt, StreamExt};
 use gloo_console::log;
 use gloo_net::websocket::{futures::WebSocket, Message};
 use wasm_bindgen_futures::spawn_local;
 
+use crate::commits::CompilerLogState;
 use crate::job_results_view::FinishedJobState;
 use crate::queue::QueueState;
 use common::commit::{CommitState, PerfReportStatus};
@@ -49,39 +52,103 @@ impl WebsocketState {
     }
 }
 
+async fn load_commits() {
+    use common::commit::Commit;
+    use gloo_net::http::{Method, Request};
+    let commit_dispatch = Dispatch::<CommitState>::new();
+    let resp: Result<Vec<Commit>, _> = Request::get("/api/commit")
+        .method(Method::GET)
+        .send()
+        .await
+        .expect("Server didn't respond. Is it running?")
+        .json()
+        .await;
+    //log!(format!("GET /api/commits: Response: {:?}", resp));
+    match resp {
+        Ok(json) => {
+            //log!(format!("got commits: {json:?}"));
+            let commit_store = CommitState::new(json);
+            commit_dispatch.set(commit_store);
+        }
+        Err(e) => log!("Error getting commit json: ", e.to_string()),
+    }
+}
+
+async fn load_queue() {
+    use gloo_net::http::{Method, Request};
+    let queue_dispatch = Dispatch::<QueueState>::new();
+    let resp: Result<Vec<Job>, _> = Request::get("/api/queue")
+        .method(Method::GET)
+        .send()
+        .await
+        .expect("Server didn't respond. Is it running?")
+        .json()
+        .await;
+    match resp {
+        Ok(json) => {
+            log!(format!("Got queue: {json:?}"));
+            // Keep `paused`, the websocket might have received it already.
+            queue_dispatch.reduce_mut(|queue_state| {
+                queue_state.queue = json.into();
+            });
+        }
+        Err(e) => log!("Error getting queue json: ", e.to_string()),
+    }
+}
+
+async fn load_finished_jobs() {
+    use gloo_net::http::{Method, Request};
+    let finished_job_dispatch = Dispatch::<FinishedJobState>::new();
+    let resp: Result<JobHistoryPage, _> = Request::get("/api/jobs?kind=Profiling")
+        .method(Method::GET)
+        .send()
+        .await
+        .expect("Server didn't respond. Is it running?")
+        .json()
+        .await;
+    match resp {
+        Ok(page) => {
+            log!(format!(
+                "Got {} of {} finished jobs.",
+                page.jobs.len(),
+                page.total
+            ));
+            // The history is sent newest first, but the results view lists the oldest first.
+            let jobs = page.jobs.into_iter().rev().collect();
+            finished_job_dispatch.set(FinishedJobState::new(jobs));
+        }
+        Err(e) => log!("Error getting job history json: ", e.to_string()),
+    }
+}
+
 // Idea: create channel in this component, put transmitter into hook (maybe use_state instead of use_store if that helps with PartialEq requirement?). receiver is moved into the use_effect_with_deps (no deps for only on first render) hook. in there I spawn_local, once for the receiver: whenever it receives, i write to the websocket. The other spawn_local receives from the websocket and writes the received data into the appropriate hooks.
 #[function_component]
 pub fn Websocket() -> Html {
     log!("Establishing Websocket...");
     let (tx, mut rx) = unbounded::<ClientMessage>();
     let dispatch = Dispatch::<WebsocketState>::new();
-    let commit_dispatch = Dispatch::<CommitState>::new();
     dispatch.set(WebsocketState::new(tx));
     use_effect_with_deps(
         move |_| {
-            use common::commit::Commit;
             use gloo_net::http::{Method, Request};
-            let commit_dispatch = commit_dispatch.clone();
             spawn_local(async move {
-                let commit_dispatch = commit_dispatch.clone();
-                let resp: Result<Vec<Commit>, _> = Request::get("/api/commit")
+                load_commits().await;
+                load_queue().await;
+                load_finished_jobs().await;
+                let profile_dispatch = Dispatch::<BuildProfileState>::new();
+                let resp: Result<Vec<BuildProfile>, _> = Request::get("/api/profiles")
                     .method(Method::GET)
                     .send()
                     .await
                     .expect("Server didn't respond. Is it running?")
                     .json()
                     .await;
-                //log!(format!("GET /api/commits: Response: {:?}", resp));
                 match resp {
-                    Ok(json) => {
-                        //log!(format!("got commits: {json:?}"));
-                        let commit_store = CommitState::new(json);
-                        commit_dispatch.set(commit_store);
-                    }
-                    Err(e) => log!("Error getting commit json: ", e.to_string()),
+                    Ok(profiles) => profile_dispatch.set(BuildProfileState(profiles)),
+                    Err(e) => log!("Error getting build profiles json: ", e.to_string()),
                 }
-                let queue_dispatch = Dispatch::<QueueState>::new();
-                let resp: Result<Vec<Job>, _> = Request::get("/api/queue")
+                let status_dispatch = Dispatch::<ServerStatus>::new();
+                let resp: Result<ServerStatus, _> = Request::get("/api/status")
                     .method(Method::GET)
                     .send()
                     .await
@@ -89,12 +156,19 @@ pub fn Websocket() -> Html {
                     .json()
                     .await;
                 match resp {
-                    Ok(json) => {
-                        log!(format!("Got queue: {json:?}"));
-                        let queue_state = QueueState::new(json);
-                        queue_dispatch.set(queue_state);
+                    Ok(status) => {
+                        if !status.sgx {
+                            // SGX is selected by default, switch to a platform that runs.
+                            Dispatch::<ProfilingConfiguration>::new().reduce_mut(|c| {
+                                c.platforms.remove(&Platform::Sgx);
+                                if c.platforms.is_empty() {
+                                    c.platforms.insert(Platform::Native);
+                                }
+                            });
+                        }
+                        status_dispatch.set(status);
                     }
-                    Err(e) => log!("Error getting queue json: ", e.to_string()),
+                    Err(e) => log!("Error getting server status json: ", e.to_string()),
                 }
             });
             // TODO Find a way for the frontend to get the websites actual address (not localhost)
@@ -130,7 +204,10 @@ pub fn Websocket() -> Html {
                                     finished_job_state.jobs.push(finished_job.clone());
                                 });
                                 queue_state_dispatch.reduce_mut(|queue_state| {
-                                    if queue_state.queue.pop_front().is_none() {
+                                    // Jobs run in parallel, so any job in the queue may finish first.
+                                    if let Some(pos) = queue_state.queue.iter().position(|j| j.id == finished_job.id) {
+                                        queue_state.queue.remove(pos);
+                                    } else {
                                         log!("Error: Queue out of sync! Reload the page?");
                                     }
                                 });
@@ -175,6 +252,28 @@ pub fn Websocket() -> Html {
                                 });
                             }
                         },
+                        ServerMessage::JobStarted(job_id) => {
+                            queue_state_dispatch.reduce_mut(|queue_state| {
+                                if let Some(job) = queue_state.queue.iter_mut().find(|j| j.id == job_id) {
+                                    job.status = JobStatus::Running;
+                                }
+                            });
+                            // Like in the backend, the operator compiles again, and a build that restarts (eg. after preemption) only shows its new output.
+                            let compiled = queue_state_dispatch.get().queue.iter().find(|j| j.id == job_id).and_then(|j| match j.config {
+                                JobConfig::Compile(id) => Some(id),
+                                _ => None,
+                            });
+                            if let Some(id) = compiled {
+                                commit_dispatch.reduce_mut(|commit_state| {
+                                    if let Some(commit) = commit_state.get_by_id_mut(&id) {
+                                        commit.compilation = CompilationStatus::Compiling;
+                                    }
+                                });
+                                Dispatch::<CompilerLogState>::new().reduce_mut(|log_state| {
+                                    log_state.0.remove(&id);
+                                });
+                            }
+                        }
                         ServerMessage::PartialReport(job_id, report) => {
                             queue_state_dispatch.reduce_mut(|queue_state| {
                                 if let Some(mut job) = queue_state.queue.iter_mut().find(|j| j.id == job_id) {
@@ -194,6 +293,43 @@ pub fn Websocket() -> Html {
                                 }
                             });
                         }
+                        ServerMessage::RunProgress(job_id, progress) => {
+                            queue_state_dispatch.reduce_mut(|queue_state| {
+                                if let Some(job) = queue_state.queue.iter_mut().find(|j| j.id == job_id) {
+                                    job.progress = Some(progress);
+                                }
+                            });
+                        }
+                        ServerMessage::CompilerOutput(id, lines) => {
+                            Dispatch::<CompilerLogState>::new().reduce_mut(|log_state| {
+                                log_state.0.entry(id).or_default().extend(lines);
+                            });
+                        }
+                        ServerMessage::JobWaiting(job_id) => {
+                            queue_state_dispatch.reduce_mut(|queue_state| {
+                                if let Some(job) = queue_state.queue.iter_mut().find(|j| j.id == job_id) {
+                                    job.status = JobStatus::Waiting;
+                                    job.progress = None;
+                                }
+                            });
+                        }
+                        ServerMessage::QueuePaused(paused) => {
+                            queue_state_dispatch.reduce_mut(|queue_state| {
+                                queue_state.paused = paused;
+                            });
+                        }
+                        ServerMessage::QueueOrder(order) => {
+                            queue_state_dispatch.reduce_mut(|queue_state| {
+                                apply_queue_order(&mut queue_state.queue, &order);
+                            });
+                        }
+                        ServerMessage::QueueOutdated => {
+                            spawn_local(async {
+                                load_commits().await;
+                                load_queue().await;
+                                load_finished_jobs().await;
+                            });
+                        }
                     }
                 }
                 //log!("Done!");
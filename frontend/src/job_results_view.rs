@@ -25,7 +25,7 @@ pub fn JobResultView(JobResultViewProps { job }: &JobResultViewProps) -> Html {
     let commit_store = use_store_value::<CommitState>();
     let time_format = format_description!("[hour]:[minute]");
     match &job.status {
-        JobStatus::Waiting => html! { <span>{"Error!"}</span> },
+        JobStatus::Waiting | JobStatus::Running => html! { <span>{"Error!"}</span> },
         JobStatus::Done { runtime } => {
             let algs: Vec<_> = if let JobConfig::Profiling(c) = &job.config {
                 c.algorithms
@@ -1,10 +1,10 @@
 use common::commit::{CommitIdType, PerfReportStatus};
 use common::hardcoded::hardcoded_commits;
 use gloo_console::log;
-use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+mod cache;
 mod chart;
 mod commits;
 mod components;
@@ -16,6 +16,7 @@ mod perf_report;
 mod profiling;
 mod queue;
 
+use crate::cache::Cache;
 use crate::commits::Commits;
 use crate::components::websocket::Websocket;
 use crate::perf_report::PerfReport;
@@ -37,6 +38,8 @@ pub enum Route {
     PerfReport { name: String },
     #[at("/performance_report/:name/:instance")]
     PerfReportDouble { name: String, instance: usize },
+    #[at("/cache")]
+    Cache,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -66,6 +69,9 @@ fn switch(routes: Route) -> Html {
         Route::PerfReportDouble { name, instance } => html! {
             <PerfReport name={Some(name)} instance={Some(instance)} />
         },
+        Route::Cache => html! {
+            <Cache />
+        },
         Route::NotFound => html! { <main><h1>{"404"}</h1><p>{"not found in yew app"}</p></main> },
     }
 }
@@ -92,7 +98,7 @@ fn App() -> Html {
 fn main() {
     use crate::job_results_view::FinishedJobState;
     use common::commit::CommitState;
-    use common::hardcoded::{hardcoded_profiling_jobs, predefined_commit};
+    use common::hardcoded::predefined_commit;
     use yewdux::prelude::Dispatch;
 
     let finished_job_dispatch = Dispatch::<FinishedJobState>::new();
@@ -182,28 +188,7 @@ fn main() {
             }
         };
         finished_job_dispatch.set(default_job_results);
-    } else {
-        use crate::queue::QueueState;
-        if finished_job_dispatch.get().jobs.is_empty() {
-            spawn_local(async {
-                let jobs = hardcoded_profiling_jobs();
-                let queue_dispatch = Dispatch::<QueueState>::new();
-                for job in jobs {
-                    let mut queue = QueueState::clone(&queue_dispatch.get());
-                    queue.queue.push_back(job.clone());
-                    queue_dispatch.set(QueueState::clone(&queue));
-                    use gloo_net::http::{Method, Request};
-                    let resp = Request::get("/api/job")
-                        .method(Method::POST)
-                        .json(&job)
-                        .unwrap() // This should be impossible to fail.
-                        .send()
-                        .await
-                        .expect("Server didn't respond. Is it running?");
-                    log!("Sent request got: ", format!("{resp:?}"));
-                }
-            });
-        }
     }
+    // Otherwise the backend seeds its database with the hardcoded jobs and the websocket loads the job history.
     yew::Renderer::<App>::new().render();
 }
@@ -48,6 +48,12 @@ pub fn Navigation(NavigationProps { active_nav_item }: &NavigationProps) -> Html
                             <span class="ms-1 d-none d-sm-inline">{"Profiling"}</span>
                         </Link<Route>>
                     </li>
+                    <li class="nav-item">
+                        <Link<Route> classes={add_active_class("Cache", active_nav_item.clone())} to={Route::Cache}>
+                            <i class="fs-5 bi-database"></i>
+                            <span class="ms-1 d-none d-sm-inline">{"Cache"}</span>
+                        </Link<Route>>
+                    </li>
                 </ul>
             </nav>
         </div>
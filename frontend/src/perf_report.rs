@@ -8,7 +8,7 @@ use crate::{
     Route,
 };
 use common::commit::{CommitState, PerfReportStatus};
-use common::data_types::JobResult;
+use common::data_types::{JobConfig, JobResult};
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct CardChartColumnProps {
@@ -73,7 +73,19 @@ pub fn PerfReport(PerfReportProps { name, instance }: &PerfReportProps) -> Html
     };
     let findings;
     let charts;
+    let mut notice = html! {};
     if let Some(JobResult::Exp(Ok(ref report))) = commit.report {
+        let native_only = report
+            .charts
+            .iter()
+            .any(|c| matches!(c.config, JobConfig::PerfReport(ref c) if c.native_only));
+        if native_only {
+            notice = html! {
+                <div class="alert alert-warning mx-2" role="alert">
+                    {"This report was made without SGX: it only has native results, the scalability and EPC paging charts are missing."}
+                </div>
+            };
+        }
         findings = report
             .findings
             .iter()
@@ -134,6 +146,7 @@ pub fn PerfReport(PerfReportProps { name, instance }: &PerfReportProps) -> Html
                         <div class="col pt-4">
                             <h2>{format!("Performance Report for {}", commit.get_title())}</h2>
                             <div class="row">
+                                {notice}
                                 // Top row
                                 {for findings}
                             </div>
@@ -7,9 +7,10 @@ use yewdux::prelude::*;
 use yewdux_input::{Checkbox, InputDispatch};
 
 use common::data_types::{
-    describe_ui_element, Algorithm, Dataset, ExperimentType, Job, Measurement, Parameter, Platform,
-    ProfilingConfiguration, VariantNames, EPC_SIZE_KB,
+    describe_ui_element, Algorithm, BuildProfileState, Dataset, ExperimentType, Job, Measurement, Parameter, Platform,
+    ProfilingConfiguration, ServerStatus, VariantNames, EPC_SIZE_KB,
 };
+use common::queue::enqueue;
 use std::collections::HashSet;
 use std::str::FromStr;
 
@@ -32,6 +33,18 @@ fn create_popover(body: &str) -> InfoPopover {
     InfoPopover::new("".to_owned(), body.to_owned())
 }
 
+/// Parses the value of a timeout input. Empty means no limit, `None` means the value is invalid.
+pub(crate) fn parse_timeout(value: &str) -> Option<Option<u64>> {
+    match value.trim() {
+        "" => Some(None),
+        v => u64::from_str(v).ok().filter(|&secs| secs > 0).map(Some),
+    }
+}
+
+pub(crate) fn format_timeout(timeout: Option<u64>) -> String {
+    timeout.map(|secs| secs.to_string()).unwrap_or_default()
+}
+
 // #[derive(Debug, Properties, Clone, PartialEq)]
 // pub struct ProfilingMenuProps {
 //     pub algs: Vec<String>,
@@ -49,6 +62,7 @@ pub fn profiling() -> Html {
     let platforms = Platform::VARIANTS;
     let datasets = Dataset::VARIANTS;
     let commit_store = use_store_value::<CommitState>();
+    let server_status = use_store_value::<ServerStatus>();
     let algs = {
         // TODO This code is repeated in commits.rs for the baseline field.
         let mut algs = SelectDataOption::options_vec(algs);
@@ -230,7 +244,11 @@ pub fn profiling() -> Html {
     };
     let platforms: Vec<CheckboxData> = platforms
         .iter()
-        .map(|p| CheckboxData::new(&p, &p, false))
+        .map(|p| match Platform::from_str(p) {
+            Ok(Platform::SgxSim) => CheckboxData::new("SGX Simulation (no real enclave)", p, false),
+            Ok(Platform::Sgx) if !server_status.sgx => CheckboxData::new("SGX (not available)", p, true),
+            _ => CheckboxData::new(p, p, false),
+        })
         .collect();
     let platforms_popover = create_popover(describe_ui_element("Platforms"));
     let platforms_onchange = {
@@ -254,13 +272,81 @@ pub fn profiling() -> Html {
             s.sort_data = value.checked();
         })
     };
+    let repetitions_popover = create_popover(describe_ui_element("Repetitions"));
+    let repetitions_onchange = {
+        let (_store, dispatch) = use_store::<ProfilingConfiguration>();
+        dispatch.reduce_mut_callback_with(|store, e: Event| {
+            let input_num = e.target_unchecked_into::<HtmlInputElement>();
+            match u32::from_str(&input_num.value()) {
+                Ok(value) if value > 0 => store.repetitions = value,
+                _ => input_num.set_value(&store.repetitions.to_string()),
+            }
+        })
+    };
+    let run_timeout_popover = create_popover(describe_ui_element("Run Timeout"));
+    let run_timeout_onchange = {
+        let (_store, dispatch) = use_store::<ProfilingConfiguration>();
+        dispatch.reduce_mut_callback_with(|store, e: Event| {
+            let input_num = e.target_unchecked_into::<HtmlInputElement>();
+            match parse_timeout(&input_num.value()) {
+                Some(value) => store.run_timeout = value,
+                None => input_num.set_value(&format_timeout(store.run_timeout)),
+            }
+        })
+    };
+    let job_timeout_popover = create_popover(describe_ui_element("Job Timeout"));
+    let job_timeout_onchange = {
+        let (_store, dispatch) = use_store::<ProfilingConfiguration>();
+        dispatch.reduce_mut_callback_with(|store, e: Event| {
+            let input_num = e.target_unchecked_into::<HtmlInputElement>();
+            match parse_timeout(&input_num.value()) {
+                Some(value) => store.job_timeout = value,
+                None => input_num.set_value(&format_timeout(store.job_timeout)),
+            }
+        })
+    };
+    let profile_store = use_store_value::<BuildProfileState>();
+    let profiles: Vec<CheckboxData> = profile_store
+        .0
+        .iter()
+        .map(|p| CheckboxData::new(&p.name, &p.name, false))
+        .collect();
+    let profiles_popover = create_popover(describe_ui_element("Build Profiles"));
+    let profiles_onchange = {
+        let (_store, dispatch) = use_store::<ProfilingConfiguration>();
+        let profile_store = profile_store.clone();
+        dispatch.reduce_mut_callback_with(move |s, e: Event| {
+            let input_check = e.target_unchecked_into::<HtmlInputElement>();
+            let value = input_check.value();
+            let bool_val = input_check.checked();
+            let Some(profile) = profile_store.0.iter().find(|p| p.name == value) else {
+                return;
+            };
+            if bool_val {
+                s.profiles.insert(profile.clone());
+            } else {
+                s.profiles.remove(profile);
+            }
+        })
+    };
+    let historical_popover = create_popover(describe_ui_element("Include Historical Data"));
+    let historical_onchange = {
+        let (_store, dispatch) = use_store::<ProfilingConfiguration>();
+        dispatch.input_mut(|s, value: Checkbox| {
+            s.include_historical = value.checked();
+        })
+    };
+    // The server refuses jobs, eg. while TeeBench is still being built.
+    let submit_error = use_state(|| None::<String>);
     let onsubmit = {
         // send some request to server
         let store = use_store_value::<ProfilingConfiguration>();
         let queue_dispatch = Dispatch::<QueueState>::new();
+        let submit_error = submit_error.clone();
         queue_dispatch.reduce_mut_future_callback_with(move |s, _| {
             // Using different notation for clone call because otherwise Rc::clone is called.
             let store = ProfilingConfiguration::clone(&store);
+            let submit_error = submit_error.clone();
             Box::pin(async move {
                 //e.prevent_default(); // Doesn't seem to work with type="submit".
                 use common::data_types::JobConfig;
@@ -273,7 +359,15 @@ pub fn profiling() -> Html {
                     .await
                     .expect("Server didn't respond. Is it running?");
                 log!("Sent request got: ", format!("{resp:?}"));
-                s.queue.push_back(job);
+                if resp.ok() {
+                    submit_error.set(None);
+                    enqueue(&mut s.queue, job);
+                } else {
+                    submit_error.set(Some(format!(
+                        "Error: {}",
+                        resp.text().await.unwrap_or_default()
+                    )));
+                }
             })
         })
     };
@@ -283,7 +377,13 @@ pub fn profiling() -> Html {
     //     s.set_preconfigured_experiment();
     // });
     let disable_controls = store.experiment_type != ExperimentType::Custom;
-    let disable_submit = store.datasets.is_empty();
+    let disable_submit = store.datasets.is_empty()
+        || store.profiles.is_empty()
+        || !store.platforms.iter().any(|&pl| server_status.can_run(pl));
+    let submit_error_html = match &*submit_error {
+        Some(msg) => html! { <div class="alert alert-danger mt-2" role="alert">{msg.clone()}</div> },
+        None => html! {},
+    };
     html! {
         <div class="container-fluid">
             <div class="row vh-100">
@@ -321,15 +421,21 @@ pub fn profiling() -> Html {
                                         </div>
                                         <div id="tbw-profiling-form-platform" class="col-md">
                                             <InputCheckboxes title={"Platform"} data={platforms} onchange={platforms_onchange} selected={store.platforms.iter().map(|pl| pl.to_string()).collect::<Vec<_>>()} disabled={disable_controls} info_popover={platforms_popover} />
+                                            <InputCheckboxes title={"Build Profile"} data={profiles} onchange={profiles_onchange} selected={store.profiles.iter().map(|p| p.name.clone()).collect::<Vec<_>>()} disabled={false} info_popover={profiles_popover} />
                                         </div>
                                         <div class="col-md">
                                             <fieldset class="row mb-3 col-md">
                                                 <legend class="form-label invisible">{"Pre-sort data"}</legend>
                                                 <InputCheckbox label={"Pre-sort data"} onchange={sort_onchange} value={"sort_data".to_string()} selected={store.sort_data} disabled={disable_controls} info_popover={sort_popover} />
+                                                <InputCheckbox label={"Include historical data"} onchange={historical_onchange} value={"include_historical".to_string()} selected={store.include_historical} disabled={false} info_popover={historical_popover} />
                                             </fieldset>
+                                            <InputNumber label={"Repetitions"} onchange={repetitions_onchange} selected={store.repetitions.to_string()} disabled={false} info_popover={repetitions_popover} />
+                                            <InputNumber label={"Run timeout (s)"} onchange={run_timeout_onchange} selected={format_timeout(store.run_timeout)} disabled={false} info_popover={run_timeout_popover} />
+                                            <InputNumber label={"Job timeout (s)"} onchange={job_timeout_onchange} selected={format_timeout(store.job_timeout)} disabled={false} info_popover={job_timeout_popover} />
                                         </div>
                                     </div>
                                     <button id="tbw-profiling-form-run" class="btn btn-primary" type="button" onclick={onsubmit} disabled={disable_submit} >{"Run experiment"}</button>
+                                    {submit_error_html}
                                 </div>
                             </form>
                             <JobResultsView />
@@ -1,20 +1,32 @@
 use std::collections::VecDeque;
 use time::macros::format_description;
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 use yewdux::prelude::*;
 
 use crate::components::tag::Tag;
 
-use common::data_types::{Job, JobConfig, JobIdType};
+use common::data_types::{Job, JobConfig, JobIdType, JobPriority, JobStatus};
+use common::queue::{bump_job, move_job, set_priority};
 
 #[derive(Debug, PartialEq, Properties)]
 struct QueueItemProps {
     job: Job,
     running: bool,
+    /// Another job was dragged here. Waiting jobs can be dragged.
+    ondragstart: Callback<()>,
+    ondrop: Callback<()>,
 }
 
 #[function_component]
-fn QueueItem(QueueItemProps { job, running }: &QueueItemProps) -> Html {
+fn QueueItem(
+    QueueItemProps {
+        job,
+        running,
+        ondragstart,
+        ondrop,
+    }: &QueueItemProps,
+) -> Html {
     let time_format = format_description!("[hour]:[minute]");
     let (spinner, desc) = if *running {
         (
@@ -23,7 +35,10 @@ fn QueueItem(QueueItemProps { job, running }: &QueueItemProps) -> Html {
                     <span class="visually-hidden">{"Running..."}</span>
                 </div>
             },
-            "running...",
+            match &job.progress {
+                Some(p) => format!("run {}/{}", p.run, p.total),
+                None => "running...".to_string(),
+            },
         )
     } else {
         (
@@ -32,7 +47,7 @@ fn QueueItem(QueueItemProps { job, running }: &QueueItemProps) -> Html {
                     <span class="visually-hidden">{"Waiting..."}</span>
                 </div>
             },
-            "waiting...",
+            "waiting...".to_string(),
         )
     };
     let algs: Vec<_> = job
@@ -41,14 +56,37 @@ fn QueueItem(QueueItemProps { job, running }: &QueueItemProps) -> Html {
         .into_iter()
         .map(|a| html! { <Tag text={a} /> })
         .collect();
+    let ondragstart = {
+        let ondragstart = ondragstart.clone();
+        Callback::from(move |e: DragEvent| {
+            // Firefox only starts dragging with some data.
+            if let Some(data) = e.data_transfer() {
+                let _ = data.set_data("text/plain", "");
+            }
+            ondragstart.emit(());
+        })
+    };
+    // Without this, nothing can be dropped here.
+    let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+    let ondrop = {
+        let ondrop = ondrop.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            ondrop.emit(());
+        })
+    };
     html! {
-        <li class="list-group-item" title={format!("{}", job.config)}>
+        <li class="list-group-item" title={format!("{}", job.config)} draggable={(!running).to_string()} {ondragstart} {ondragover} {ondrop}>
             <div class="">
                 <span class="px-1">{spinner}</span>
                 <span class="px-1">{"Submitted at: "}<span class="fw-bold">{format!("{} ", job.submitted.format(time_format).unwrap())}</span></span>
                 <span class="px-1">{for algs}</span>
-                <span class="px-1">{desc}</span>
-                <span class="float-end"><RemoveJobButton id={job.id} /></span>
+                <span class="px-1" title={job.progress.as_ref().map(|p| p.command.clone())}>{desc}</span>
+                <span class="float-end">
+                    <JobPrioritySelect id={job.id} priority={job.priority} disabled={*running} />
+                    <BumpJobButton id={job.id} disabled={*running} />
+                    <RemoveJobButton id={job.id} />
+                </span>
             </div>
         </li>
     }
@@ -73,13 +111,8 @@ fn QueueItem(QueueItemProps { job, running }: &QueueItemProps) -> Html {
 #[derive(Debug, Clone, Default, PartialEq, Store)]
 pub struct QueueState {
     pub queue: VecDeque<Job>,
-}
-
-impl QueueState {
-    pub fn new(jobs: Vec<Job>) -> Self {
-        let queue: VecDeque<Job> = jobs.into();
-        QueueState { queue }
-    }
+    /// No new jobs start on the server, see `ClientMessage::PauseQueue`.
+    pub paused: bool,
 }
 
 #[derive(Debug, PartialEq, Properties)]
@@ -89,21 +122,43 @@ pub struct QueueProps {
 
 #[function_component]
 pub fn Queue(QueueProps { filter_by }: &QueueProps) -> Html {
-    let (queue_store, _queue_dispatch) = use_store::<QueueState>();
+    let (queue_store, queue_dispatch) = use_store::<QueueState>();
+    let websocket_store = use_store_value::<WebsocketState>();
+    let dragged = use_state(|| None::<JobIdType>);
     let queue: Vec<Html> = queue_store
         .queue
         .iter()
-        .filter(|j| {
+        // The position in the whole queue, not just the shown jobs.
+        .enumerate()
+        .filter(|(_, j)| {
             if let Some(filter_by) = filter_by {
                 std::mem::discriminant(filter_by) == std::mem::discriminant(&j.config)
             } else {
                 true
             }
         })
-        .enumerate()
-        .map(|(i, j)| {
-            let running = if i == 0 { true } else { false };
-            html! { <QueueItem job={j.clone()} running={running} /> }
+        .map(|(position, j)| {
+            let running = j.status == JobStatus::Running;
+            let ondragstart = {
+                let dragged = dragged.clone();
+                let id = j.id;
+                Callback::from(move |_| dragged.set(Some(id)))
+            };
+            let ondrop = {
+                let dragged = dragged.clone();
+                let websocket_store = websocket_store.clone();
+                let queue_dispatch = queue_dispatch.clone();
+                Callback::from(move |_| {
+                    if let Some(id) = *dragged {
+                        websocket_store.send(ClientMessage::MoveJob(id, position));
+                        queue_dispatch.reduce_mut(|s| {
+                            move_job(&mut s.queue, &id, position);
+                        });
+                    }
+                    dragged.set(None);
+                })
+            };
+            html! { <QueueItem job={j.clone()} running={running} {ondragstart} {ondrop} /> }
         })
         .collect();
 
@@ -112,6 +167,7 @@ pub fn Queue(QueueProps { filter_by }: &QueueProps) -> Html {
         //     <h3 class="fs-5">{"Queue"}</h3>
         //     <ul class="list-group">
         <>
+            <QueueHeader />
             {for queue}
             <ClearQueueButton />
         </>
@@ -122,6 +178,64 @@ pub fn Queue(QueueProps { filter_by }: &QueueProps) -> Html {
 
 use crate::components::websocket::WebsocketState;
 use common::data_types::ClientMessage;
+
+/// Shows whether the queue is paused and pauses or resumes it.
+#[function_component]
+pub fn QueueHeader() -> Html {
+    let queue_store = use_store_value::<QueueState>();
+    let websocket_store = use_store_value::<WebsocketState>();
+    let onclick_pause = {
+        let websocket_store = websocket_store.clone();
+        Callback::from(move |_| websocket_store.send(ClientMessage::PauseQueue { preempt: false }))
+    };
+    let onclick_preempt = {
+        let websocket_store = websocket_store.clone();
+        Callback::from(move |_| websocket_store.send(ClientMessage::PauseQueue { preempt: true }))
+    };
+    let onclick_resume = Callback::from(move |_| websocket_store.send(ClientMessage::ResumeQueue));
+    let running = queue_store
+        .queue
+        .iter()
+        .any(|j| j.status == JobStatus::Running);
+    let preempt_button = html! {
+        <button class="btn btn-sm btn-outline-warning me-1" type="button" title="Running jobs are stopped and wait in the queue again" disabled={!running} onclick={onclick_preempt}>
+            <i class="bi-stop-fill"></i>{" Pause now"}
+        </button>
+    };
+    let (status, buttons) = if queue_store.paused {
+        (
+            html! { <span class="badge text-bg-warning">{"Paused"}</span> },
+            html! {
+                <>
+                    {preempt_button}
+                    <button class="btn btn-sm btn-success" type="button" onclick={onclick_resume}>
+                        <i class="bi-play-fill"></i>{" Resume"}
+                    </button>
+                </>
+            },
+        )
+    } else {
+        (
+            html! { <span class="badge text-bg-success">{"Running"}</span> },
+            html! {
+                <>
+                    <button class="btn btn-sm btn-warning me-1" type="button" title="Running jobs finish, no new ones start" onclick={onclick_pause}>
+                        <i class="bi-pause-fill"></i>{" Pause"}
+                    </button>
+                    {preempt_button}
+                </>
+            },
+        )
+    };
+    html! {
+        <li class="list-group-item d-flex align-items-center">
+            <span class="fw-bold me-2">{"Queue"}</span>
+            {status}
+            <span class="ms-auto">{buttons}</span>
+        </li>
+    }
+}
+
 #[function_component]
 pub fn ClearQueueButton() -> Html {
     let (queue_store, queue_dispatch) = use_store::<QueueState>();
@@ -138,6 +252,69 @@ pub fn ClearQueueButton() -> Html {
     }
 }
 
+#[derive(PartialEq, Properties)]
+pub struct JobPrioritySelectProps {
+    pub id: JobIdType,
+    pub priority: JobPriority,
+    pub disabled: bool,
+}
+
+#[function_component]
+pub fn JobPrioritySelect(
+    JobPrioritySelectProps {
+        id,
+        priority,
+        disabled,
+    }: &JobPrioritySelectProps,
+) -> Html {
+    let queue_dispatch = Dispatch::<QueueState>::new();
+    let onchange = {
+        let websocket_store = use_store_value::<WebsocketState>();
+        let id = *id;
+        queue_dispatch.reduce_mut_callback_with(move |s, e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            if let Ok(priority) = select.value().parse::<JobPriority>() {
+                websocket_store.send(ClientMessage::SetJobPriority(id, priority));
+                set_priority(&mut s.queue, &id, priority);
+            }
+        })
+    };
+    let options: Vec<Html> = [JobPriority::High, JobPriority::Normal, JobPriority::Low]
+        .iter()
+        .map(|p| html! { <option value={p.to_string()} selected={p == priority}>{p.to_string()}</option> })
+        .collect();
+    html! {
+        <select class="form-select form-select-sm d-inline-block w-auto me-1" title="Priority" disabled={*disabled} {onchange}>
+            {for options}
+        </select>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BumpJobButtonProps {
+    pub id: JobIdType,
+    pub disabled: bool,
+}
+
+/// Makes the job the next one to start.
+#[function_component]
+pub fn BumpJobButton(BumpJobButtonProps { id, disabled }: &BumpJobButtonProps) -> Html {
+    let queue_dispatch = Dispatch::<QueueState>::new();
+    let onclick = {
+        let websocket_store = use_store_value::<WebsocketState>();
+        let id = *id;
+        queue_dispatch.reduce_mut_callback(move |s| {
+            websocket_store.send(ClientMessage::BumpJob(id));
+            bump_job(&mut s.queue, &id);
+        })
+    };
+    html! {
+        <button class="btn btn-outline-primary me-1" title="Run next" disabled={*disabled} {onclick}>
+            <i class="bi-chevron-double-up"></i>
+        </button>
+    }
+}
+
 #[derive(PartialEq, Properties)]
 pub struct RemoveJobButtonProps {
     pub id: JobIdType,
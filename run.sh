@@ -25,14 +25,9 @@ get_abs_filename() {
     echo "$(cd "$(dirname "$1")" && pwd)/$(basename "$1")"
 }
 
-cleanup_cache_db() {
-    # This only works as long as we didn't change the directory since starting the backend executable
-    rm --interactive=never "${TEEBENCHWEB_SQLITE_FILE}"
-    kill -term $$
-}
-
 run_backend() (
-    trap "cleanup_cache_db" INT # Runs cleanup_cache_db on Ctrl+C
+    # Ctrl+C reaches the backend, which shuts down gracefully. The no-op handler keeps this subshell waiting for it.
+    trap ":" INT
 #     if [[ -z "${TEEBENCHWEB_RUN_DIR}" ]]; then
 #         cargo build --bin fake_teebench
 #         mkdir -p ../fake_teebench/Joins/TBW
@@ -55,7 +50,6 @@ run_backend() (
 #     fi
     cargo build --bin backend
     sudo -E ./../target/debug/backend
-    cleanup_cache_db
 )
 
 build_frontend() (